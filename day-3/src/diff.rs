@@ -0,0 +1,211 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+};
+
+use crate::{
+    collect_gears, collect_numbers, gear_numbers, is_part, parse_into_grid, process_part_1,
+    process_part_2, SchemaItem, Span,
+};
+
+type GearChange = ((usize, usize), Option<u32>, Option<u32>);
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SchematicDiff {
+    pub appeared: Vec<(Span, u32)>,
+    pub disappeared: Vec<(Span, u32)>,
+    // (before, after, old value, new value)
+    pub changed: Vec<(Span, Span, u32, u32)>,
+    pub gears: Vec<GearChange>,
+    pub part_1: (u32, u32),
+    pub part_2: (u32, u32),
+}
+
+fn part_numbers(grid: &[Vec<SchemaItem>]) -> BTreeMap<Span, u32> {
+    collect_numbers(grid)
+        .into_iter()
+        .filter(|((start, end), _)| is_part(grid, start, end))
+        .collect()
+}
+
+fn gear_ratios(grid: &[Vec<SchemaItem>]) -> HashMap<(usize, usize), u32> {
+    let numbers = collect_numbers(grid);
+    collect_gears(grid)
+        .into_iter()
+        .filter_map(|gear| match gear_numbers(&numbers, gear, grid)[..] {
+            [a, b] => Some((gear, a * b)),
+            _ => None,
+        })
+        .collect()
+}
+
+// The numbers in `parts` on the same row as `span` that overlap it. Numbers on a row never
+// overlap each other, so only those starting before `span` ends need checking.
+fn overlapping(parts: &BTreeMap<Span, u32>, span: &Span) -> Vec<Span> {
+    let ((row, start), (_, end)) = *span;
+    parts
+        .range(((row, 0), (row, 0))..=((row, end), (row, usize::MAX)))
+        .rev()
+        .take_while(|((_, (_, other_end)), _)| *other_end >= start)
+        .map(|(other, _)| *other)
+        .collect()
+}
+
+// A number is the same number in both schematics when it overlaps exactly one number on the
+// same row and that number overlaps only it, so a number that gains or loses a digit is changed
+// rather than replaced.
+fn counterpart(
+    parts: &BTreeMap<Span, u32>,
+    others: &BTreeMap<Span, u32>,
+    span: &Span,
+) -> Option<Span> {
+    match overlapping(others, span)[..] {
+        [other] if overlapping(parts, &other) == [*span] => Some(other),
+        _ => None,
+    }
+}
+
+fn dimensions(grid: &[Vec<SchemaItem>]) -> Vec<usize> {
+    grid.iter().map(|row| row.len()).collect()
+}
+
+pub fn diff(before: &str, after: &str) -> Result<SchematicDiff, String> {
+    let before_grid = parse_into_grid(before);
+    let after_grid = parse_into_grid(after);
+    if dimensions(&before_grid) != dimensions(&after_grid) {
+        return Err("schematics are not the same size".to_string());
+    }
+
+    let before_parts = part_numbers(&before_grid);
+    let after_parts = part_numbers(&after_grid);
+    let mut result = SchematicDiff {
+        part_1: (process_part_1(before), process_part_1(after)),
+        part_2: (process_part_2(before), process_part_2(after)),
+        ..Default::default()
+    };
+
+    for (span, old) in &before_parts {
+        match counterpart(&before_parts, &after_parts, span) {
+            None => result.disappeared.push((*span, *old)),
+            Some(other) if after_parts[&other] != *old => {
+                result
+                    .changed
+                    .push((*span, other, *old, after_parts[&other]))
+            }
+            Some(_) => (),
+        }
+    }
+    for (span, new) in &after_parts {
+        if counterpart(&after_parts, &before_parts, span).is_none() {
+            result.appeared.push((*span, *new));
+        }
+    }
+
+    let before_gears = gear_ratios(&before_grid);
+    let after_gears = gear_ratios(&after_grid);
+    let gears: BTreeSet<_> = before_gears.keys().chain(after_gears.keys()).collect();
+    for gear in gears {
+        let old = before_gears.get(gear).copied();
+        let new = after_gears.get(gear).copied();
+        if old != new {
+            result.gears.push((*gear, old, new));
+        }
+    }
+
+    result.appeared.sort();
+    result.disappeared.sort();
+    result.changed.sort();
+    Ok(result)
+}
+
+fn ratio(value: Option<u32>) -> String {
+    value.map_or("-".to_string(), |v| v.to_string())
+}
+
+impl fmt::Display for SchematicDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (((x, y), _), number) in &self.appeared {
+            writeln!(f, "+ {number} at {x},{y}")?;
+        }
+        for (((x, y), _), number) in &self.disappeared {
+            writeln!(f, "- {number} at {x},{y}")?;
+        }
+        for (_, ((x, y), _), old, new) in &self.changed {
+            writeln!(f, "~ {old} -> {new} at {x},{y}")?;
+        }
+        for ((x, y), old, new) in &self.gears {
+            writeln!(f, "* gear at {x},{y}: {} -> {}", ratio(*old), ratio(*new))?;
+        }
+        writeln!(f, "part 1: {} -> {}", self.part_1.0, self.part_1.1)?;
+        write!(f, "part 2: {} -> {}", self.part_2.0, self.part_2.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schematic_diff() {
+        let before = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+        let after = "467..114..
+...*......
+..36..633.
+......#...
+617.......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598.1";
+
+        let result = diff(before, after).unwrap();
+
+        assert_eq!(
+            result,
+            SchematicDiff {
+                appeared: vec![],
+                disappeared: vec![(((4, 0), (4, 2)), 617)],
+                changed: vec![(((2, 2), (2, 3)), ((2, 2), (2, 3)), 35, 36)],
+                gears: vec![((1, 3), Some(16345), Some(16812))],
+                part_1: (4361, 3745),
+                part_2: (467835, 468302),
+            }
+        );
+    }
+
+    #[test]
+    fn overlapping_numbers() {
+        let result = diff("35.\n*..", "355\n*..").unwrap();
+
+        assert_eq!(
+            result.changed,
+            vec![(((0, 0), (0, 1)), ((0, 0), (0, 2)), 35, 355)]
+        );
+        assert_eq!((result.appeared, result.disappeared), (vec![], vec![]));
+
+        let result = diff("355\n.*.", "3.5\n.*.").unwrap();
+
+        assert_eq!(result.changed, vec![]);
+        assert_eq!(result.disappeared, vec![(((0, 0), (0, 2)), 355)]);
+        assert_eq!(
+            result.appeared,
+            vec![(((0, 0), (0, 0)), 3), (((0, 2), (0, 2)), 5)]
+        );
+        assert_eq!(result.gears, vec![((1, 1), None, Some(15))]);
+    }
+
+    #[test]
+    fn different_sizes() {
+        assert!(diff("..\n..", "...\n...").is_err());
+    }
+}
@@ -2,7 +2,68 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 
+mod diff;
+mod query;
+mod stream;
+
 fn main() {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("stream") => {
+            let totals = match args.next() {
+                Some(path) => {
+                    let file = std::fs::File::open(path).expect("could not open schematic");
+                    stream::process_stream(std::io::BufReader::new(file))
+                }
+                None => stream::process_stream(std::io::stdin().lock()),
+            };
+            let totals = match totals {
+                Ok(totals) => totals,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            };
+
+            println!("{}", totals.part_1);
+            println!("{}", totals.part_2);
+            return;
+        }
+        Some("diff") => {
+            let (Some(before), Some(after)) = (args.next(), args.next()) else {
+                eprintln!("usage: day-3 diff <before> <after>");
+                return;
+            };
+            let before = std::fs::read_to_string(before).expect("could not read schematic");
+            let after = std::fs::read_to_string(after).expect("could not read schematic");
+            match diff::diff(&before, &after) {
+                Ok(diff) => println!("{diff}"),
+                Err(e) => eprintln!("{e}"),
+            }
+            return;
+        }
+        Some("query") => {
+            let (Some(x), Some(y)) = (args.next(), args.next()) else {
+                eprintln!("usage: day-3 query <row> <col> [schematic]");
+                return;
+            };
+            let input = match args.next() {
+                Some(path) => std::fs::read_to_string(path).expect("could not read schematic"),
+                None => include_str!("./input.txt").to_string(),
+            };
+            let position = (
+                x.parse().expect("row should be a number"),
+                y.parse().expect("col should be a number"),
+            );
+            match query::query(&input, position) {
+                Some(cell) => println!("{cell}"),
+                None => eprintln!("{},{} is outside the schematic", position.0, position.1),
+            }
+            return;
+        }
+        _ => (),
+    }
+
     let input = include_str!("./input.txt");
 
     let part_1 = process_part_1(input);
@@ -42,13 +103,15 @@ fn parse_into_grid(input: &str) -> Vec<Vec<SchemaItem>> {
         .collect()
 }
 
-fn collect_numbers(grid: &Vec<Vec<SchemaItem>>) -> HashMap<((usize, usize), (usize, usize)), u32> {
+type Span = ((usize, usize), (usize, usize));
+
+fn collect_numbers(grid: &[Vec<SchemaItem>]) -> HashMap<Span, u32> {
     let mut map = HashMap::new();
     for (x, row) in grid.iter().enumerate() {
-        let groups = row.iter().enumerate().group_by(|(_, item)| match item {
-            SchemaItem::Number(_) => true,
-            _ => false,
-        });
+        let groups = row
+            .iter()
+            .enumerate()
+            .group_by(|(_, item)| matches!(item, SchemaItem::Number(_)));
         for group in &groups {
             let group: Vec<_> = group
                 .1
@@ -57,7 +120,7 @@ fn collect_numbers(grid: &Vec<Vec<SchemaItem>>) -> HashMap<((usize, usize), (usi
                     _ => None,
                 })
                 .collect();
-            if group.len() > 0 {
+            if !group.is_empty() {
                 let num = group.iter().map(|g| g.1).join("");
                 let num = num.parse::<u32>().unwrap();
                 let start = (x, group[0].0);
@@ -69,7 +132,7 @@ fn collect_numbers(grid: &Vec<Vec<SchemaItem>>) -> HashMap<((usize, usize), (usi
     map.clone()
 }
 
-fn collect_gears(grid: &Vec<Vec<SchemaItem>>) -> Vec<(usize, usize)> {
+fn collect_gears(grid: &[Vec<SchemaItem>]) -> Vec<(usize, usize)> {
     let mut gears = vec![];
     for (x, row) in grid.iter().enumerate() {
         for (y, item) in row.iter().enumerate() {
@@ -81,47 +144,99 @@ fn collect_gears(grid: &Vec<Vec<SchemaItem>>) -> Vec<(usize, usize)> {
     gears.clone()
 }
 
+fn gear_numbers(
+    numbers: &HashMap<Span, u32>,
+    gear: (usize, usize),
+    grid: &[Vec<SchemaItem>],
+) -> Vec<u32> {
+    let search_positions = [
+        (gear.0.saturating_sub(1), gear.1.saturating_sub(1)),
+        (gear.0.saturating_sub(1), gear.1),
+        (gear.0.saturating_sub(1), gear.1 + 1),
+        (gear.0, gear.1.saturating_sub(1)),
+        (gear.0, gear.1 + 1),
+        (gear.0 + 1, gear.1.saturating_sub(1)),
+        (gear.0 + 1, gear.1),
+        (gear.0 + 1, gear.1 + 1),
+    ];
+
+    let mut number_positions = vec![];
+    for search_position in search_positions {
+        if search_position.0 >= grid.len() || search_position.1 >= grid[search_position.0].len() {
+            continue;
+        }
+        let test = &grid[search_position.0][search_position.1];
+        if let SchemaItem::Number(_) = test {
+            number_positions.push(search_position);
+        }
+    }
+    let mut number_matches = vec![];
+    for ((start, end), num) in numbers.iter() {
+        if number_positions.contains(start) || number_positions.contains(end) {
+            number_matches.push(*num);
+        }
+    }
+    number_matches
+}
+
 fn find_overlaps(
-    numbers: HashMap<((usize, usize), (usize, usize)), u32>,
+    numbers: HashMap<Span, u32>,
     gears: Vec<(usize, usize)>,
     grid: Vec<Vec<SchemaItem>>,
 ) -> Vec<(u32, u32)> {
     let mut result = vec![];
     for gear in gears {
-        let search_positions = [
-            (gear.0.saturating_sub(1), gear.1.saturating_sub(1)),
-            (gear.0.saturating_sub(1), gear.1),
-            (gear.0.saturating_sub(1), gear.1 + 1),
-            (gear.0, gear.1.saturating_sub(1)),
-            (gear.0, gear.1 + 1),
-            (gear.0 + 1, gear.1.saturating_sub(1)),
-            (gear.0 + 1, gear.1),
-            (gear.0 + 1, gear.1 + 1),
-        ];
-
-        let mut number_positions = vec![];
-        for search_position in search_positions {
-            if search_position.0 > grid.len() || search_position.1 > grid[0].len() {
+        let number_matches = gear_numbers(&numbers, gear, &grid);
+
+        if number_matches.len() == 2 {
+            result.push((number_matches[0], number_matches[1]));
+        }
+    }
+    result
+}
+
+fn is_part(grid: &[Vec<SchemaItem>], start: &(usize, usize), end: &(usize, usize)) -> bool {
+    // start.0 - 1 to end.0 + 1
+    //      check [x][start.1] and [x][end.1]
+    //      start.1 - 1 to end.1 - 1
+    //      start.1 + 1 to end.1 + 1
+    //      if grid[x][y] === symbol
+    //          it's a part
+    for x in start.0.saturating_sub(1)..=(end.0 + 1) {
+        if x > grid.len() - 1 {
+            continue;
+        }
+
+        let item = &grid[x][start.1];
+        if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
+            return true;
+        }
+        let item = &grid[x][end.1];
+        if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
+            return true;
+        }
+
+        for y in start.1.saturating_sub(1)..=end.1.saturating_sub(1) {
+            if y > grid[x].len() {
                 continue;
             }
-            let test = &grid[search_position.0][search_position.1];
-            match test {
-                SchemaItem::Number(_) => number_positions.push(search_position),
-                _ => (),
-            }
-        }
-        let mut number_matches = vec![];
-        for ((start, end), num) in numbers.iter() {
-            if number_positions.contains(&start) || number_positions.contains(&end) {
-                number_matches.push(*num);
+            let item = &grid[x][y];
+            if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
+                return true;
             }
         }
 
-        if number_matches.len() == 2 {
-            result.push((number_matches[0], number_matches[1]));
+        for y in (start.1 + 1)..=(end.1 + 1) {
+            if y > grid[x].len() - 1 {
+                continue;
+            }
+            let item = &grid[x][y];
+            if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
+                return true;
+            }
         }
     }
-    result
+    false
 }
 
 fn process_part_1(input: &str) -> u32 {
@@ -130,49 +245,8 @@ fn process_part_1(input: &str) -> u32 {
     // for each number in the list above, check to see if they're adjacent to symbols in grid
     let result: u32 = numbers
         .iter()
-        .map(|((start, end), number)| {
-            // start.0 - 1 to end.0 + 1
-            //      check [x][start.1] and [x][end.1]
-            //      start.1 - 1 to end.1 - 1
-            //      start.1 + 1 to end.1 + 1
-            //      if grid[x][y] === symbol
-            //          inc counter by number
-            for x in start.0.saturating_sub(1)..=(end.0 + 1) {
-                if x > grid.len() - 1 {
-                    continue;
-                }
-
-                let item = &grid[x][start.1];
-                if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
-                    return *number;
-                }
-                let item = &grid[x][end.1];
-                if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
-                    return *number;
-                }
-
-                for y in start.1.saturating_sub(1)..=end.1.saturating_sub(1) {
-                    if y > grid[x].len() {
-                        continue;
-                    }
-                    let item = &grid[x][y];
-                    if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
-                        return *number;
-                    }
-                }
-
-                for y in (start.1 + 1)..=(end.1 + 1) {
-                    if y > grid[x].len() - 1 {
-                        continue;
-                    }
-                    let item = &grid[x][y];
-                    if *item == SchemaItem::Symbol || *item == SchemaItem::Gear {
-                        return *number;
-                    }
-                }
-            }
-            0
-        })
+        .filter(|((start, end), _)| is_part(&grid, start, end))
+        .map(|(_, number)| number)
         .sum();
     result
 }
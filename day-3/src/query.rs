@@ -0,0 +1,153 @@
+use std::fmt;
+
+use crate::{collect_numbers, gear_numbers, is_part, parse_into_grid, SchemaItem, Span};
+
+#[derive(Debug, PartialEq)]
+pub struct NumberCell {
+    pub value: u32,
+    pub span: Span,
+    pub is_part: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Cell {
+    pub item: SchemaItem,
+    pub number: Option<NumberCell>,
+    pub symbols: Vec<((usize, usize), char)>,
+    pub gear_ratio: Option<u32>,
+}
+
+fn neighbours(lines: &[&str], span: Span) -> Vec<((usize, usize), char)> {
+    let ((x, start), (_, end)) = span;
+    let mut symbols = vec![];
+    for (i, line) in lines
+        .iter()
+        .enumerate()
+        .take(x + 2)
+        .skip(x.saturating_sub(1))
+    {
+        for (y, c) in line
+            .chars()
+            .enumerate()
+            .take(end + 2)
+            .skip(start.saturating_sub(1))
+        {
+            let inside = i == x && (start..=end).contains(&y);
+            if !inside && matches!(SchemaItem::from(c), SchemaItem::Symbol | SchemaItem::Gear) {
+                symbols.push(((i, y), c));
+            }
+        }
+    }
+    symbols
+}
+
+pub fn query(input: &str, position: (usize, usize)) -> Option<Cell> {
+    let lines: Vec<_> = input.lines().collect();
+    let grid = parse_into_grid(input);
+    let item = SchemaItem::from(lines.get(position.0)?.chars().nth(position.1)?);
+    let numbers = collect_numbers(&grid);
+
+    let number = numbers
+        .iter()
+        .find(|((start, end), _)| start.0 == position.0 && (start.1..=end.1).contains(&position.1))
+        .map(|((start, end), value)| NumberCell {
+            value: *value,
+            span: (*start, *end),
+            is_part: is_part(&grid, start, end),
+        });
+
+    let span = number.as_ref().map_or((position, position), |n| n.span);
+    let gear_ratio = match item {
+        SchemaItem::Gear => match gear_numbers(&numbers, position, &grid)[..] {
+            [a, b] => Some(a * b),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    Some(Cell {
+        item,
+        number,
+        symbols: neighbours(&lines, span),
+        gear_ratio,
+    })
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "item: {:?}", self.item)?;
+        if let Some(number) = &self.number {
+            let ((x, start), (_, end)) = number.span;
+            writeln!(
+                f,
+                "number: {} at {x},{start}..={end} (part: {})",
+                number.value, number.is_part
+            )?;
+        }
+        if let Some(ratio) = self.gear_ratio {
+            writeln!(f, "gear ratio: {ratio}")?;
+        }
+        write!(f, "symbols:")?;
+        for ((x, y), c) in &self.symbols {
+            write!(f, " {c} at {x},{y}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn number_cell() {
+        let result = query(INPUT, (2, 3)).unwrap();
+
+        assert_eq!(
+            result,
+            Cell {
+                item: SchemaItem::Number(5),
+                number: Some(NumberCell {
+                    value: 35,
+                    span: ((2, 2), (2, 3)),
+                    is_part: true,
+                }),
+                symbols: vec![((1, 3), '*')],
+                gear_ratio: None,
+            }
+        );
+    }
+
+    #[test]
+    fn gear_cell() {
+        let result = query(INPUT, (8, 5)).unwrap();
+
+        assert_eq!(result.item, SchemaItem::Gear);
+        assert_eq!(result.gear_ratio, Some(755 * 598));
+        assert_eq!(result.symbols, vec![]);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        assert_eq!(query(INPUT, (10, 0)), None);
+        assert_eq!(query(INPUT, (0, 10)), None);
+    }
+
+    #[test]
+    fn number_not_a_part() {
+        let result = query(INPUT, (0, 6)).unwrap();
+
+        assert!(!result.number.unwrap().is_part);
+    }
+}
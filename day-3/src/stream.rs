@@ -0,0 +1,173 @@
+use std::io::{self, BufRead};
+
+fn is_symbol(byte: u8) -> bool {
+    !byte.is_ascii_digit() && byte != b'.'
+}
+
+fn has_symbol(row: &[u8], from: usize, to: usize) -> bool {
+    (from..=to).any(|y| row.get(y).is_some_and(|b| is_symbol(*b)))
+}
+
+// None if a number doesn't fit in a u64.
+fn number_spans(row: &[u8]) -> Option<Vec<(usize, usize, u64)>> {
+    let mut spans = vec![];
+    let mut y = 0;
+    while y < row.len() {
+        if !row[y].is_ascii_digit() {
+            y += 1;
+            continue;
+        }
+        let start = y;
+        let mut number = 0_u64;
+        while y < row.len() && row[y].is_ascii_digit() {
+            number = number
+                .checked_mul(10)?
+                .checked_add(u64::from(row[y] - b'0'))?;
+            y += 1;
+        }
+        spans.push((start, y - 1, number));
+    }
+    Some(spans)
+}
+
+// A line of the schematic with its numbers already read, so each line is only parsed once.
+#[derive(Default)]
+struct Row {
+    line: usize,
+    bytes: Vec<u8>,
+    spans: Vec<(usize, usize, u64)>,
+}
+
+impl Row {
+    fn new(line: usize, text: &str) -> io::Result<Self> {
+        let bytes = text.trim_end().as_bytes().to_vec();
+        let spans = number_spans(&bytes).ok_or_else(|| invalid(line, "number is too large"))?;
+        Ok(Self { line, bytes, spans })
+    }
+
+    fn numbers_touching(&self, col: usize) -> impl Iterator<Item = u64> + '_ {
+        self.spans
+            .iter()
+            .filter(move |(start, end, _)| *start <= col + 1 && *end + 1 >= col)
+            .map(|(_, _, number)| *number)
+    }
+}
+
+fn invalid(line: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {line}: {message}"),
+    )
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Totals {
+    pub part_1: u64,
+    pub part_2: u64,
+}
+
+impl Totals {
+    // `row` is checked against the rows either side of it, which are empty at the edges.
+    fn add_row(&mut self, above: &Row, row: &Row, below: &Row) -> io::Result<()> {
+        let overflow = || invalid(row.line, "total is too large");
+
+        for (start, end, number) in &row.spans {
+            let from = start.saturating_sub(1);
+            let to = end + 1;
+            if has_symbol(&above.bytes, from, to)
+                || has_symbol(&row.bytes, from, to)
+                || has_symbol(&below.bytes, from, to)
+            {
+                self.part_1 = self.part_1.checked_add(*number).ok_or_else(overflow)?;
+            }
+        }
+
+        for (col, _) in row.bytes.iter().enumerate().filter(|(_, b)| **b == b'*') {
+            let numbers: Vec<_> = [above, row, below]
+                .iter()
+                .flat_map(|r| r.numbers_touching(col))
+                .collect();
+            if numbers.len() == 2 {
+                let ratio = numbers[0].checked_mul(numbers[1]).ok_or_else(overflow)?;
+                self.part_2 = self.part_2.checked_add(ratio).ok_or_else(overflow)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Only three rows are held at once, so the schematic can be any number of lines long.
+pub fn process_stream<R: BufRead>(reader: R) -> io::Result<Totals> {
+    let mut totals = Totals::default();
+    let mut above = Row::default();
+    let mut row: Option<Row> = None;
+
+    for (i, line) in reader.lines().enumerate() {
+        let below = Row::new(i + 1, &line?)?;
+        if let Some(current) = row.take() {
+            totals.add_row(&above, &current, &below)?;
+            above = current;
+        }
+        row = Some(below);
+    }
+    if let Some(current) = row {
+        totals.add_row(&above, &current, &Row::default())?;
+    }
+
+    Ok(totals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream() {
+        let input = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+        let result = process_stream(input.as_bytes()).unwrap();
+
+        assert_eq!(
+            result,
+            Totals {
+                part_1: 4361,
+                part_2: 467835
+            }
+        );
+    }
+
+    #[test]
+    fn stream_matches_grid() {
+        let input = include_str!("./input.txt");
+
+        let result = process_stream(input.as_bytes()).unwrap();
+
+        assert_eq!(result.part_1, crate::process_part_1(input) as u64);
+        assert_eq!(result.part_2, crate::process_part_2(input) as u64);
+    }
+
+    #[test]
+    fn overflow() {
+        let long = format!("..\n.{}#\n..", "9".repeat(20));
+        let big = "4294967296*4294967296";
+
+        for (input, message) in [
+            (long.as_str(), "line 2: number is too large"),
+            (big, "line 1: total is too large"),
+        ] {
+            let result = process_stream(input.as_bytes()).unwrap_err();
+
+            assert_eq!(result.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(result.to_string(), message);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{Cascade, Game};
+
+#[derive(Debug, PartialEq)]
+pub struct LedgerEntry {
+    pub id: u32,
+    pub matches: u32,
+    pub copies: u64,
+    // (card id, copies won from that card)
+    pub sources: Vec<(u32, u64)>,
+}
+
+// Entries are in card id order, which is the order copies cascade in.
+pub fn ledger(games: &[Game]) -> Vec<LedgerEntry> {
+    let mut games: Vec<_> = games.iter().collect();
+    games.sort_by_key(|game| game.id);
+    let mut cascade = Cascade::default();
+
+    games
+        .into_iter()
+        .map(|game| {
+            let matches = game.winner_count();
+            let (copies, sources) = cascade.add(game.id, matches);
+            LedgerEntry {
+                id: game.id,
+                matches,
+                copies,
+                sources,
+            }
+        })
+        .collect()
+}
+
+fn sources(entry: &LedgerEntry) -> String {
+    entry
+        .sources
+        .iter()
+        .map(|(id, copies)| format!("{id}x{copies}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn to_table(entries: &[LedgerEntry]) -> String {
+    let mut table = format!(
+        "{:>6} {:>7} {:>10}  {}\n",
+        "card", "matches", "copies", "from"
+    );
+    for entry in entries {
+        table.push_str(&format!(
+            "{:>6} {:>7} {:>10}  {}\n",
+            entry.id,
+            entry.matches,
+            entry.copies,
+            sources(entry)
+        ));
+    }
+    table
+}
+
+pub fn to_json(entries: &[LedgerEntry]) -> String {
+    let entries: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let sources: Vec<_> = entry
+                .sources
+                .iter()
+                .map(|(id, copies)| format!("{{\"card\":{id},\"copies\":{copies}}}"))
+                .collect();
+            format!(
+                "{{\"card\":{},\"matches\":{},\"copies\":{},\"from\":[{}]}}",
+                entry.id,
+                entry.matches,
+                entry.copies,
+                sources.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cascade() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        let games: Vec<Game> = input.lines().map(|line| line.parse().unwrap()).collect();
+
+        let result = ledger(&games);
+
+        assert_eq!(
+            result.iter().map(|e| e.copies).collect::<Vec<_>>(),
+            vec![1, 2, 4, 8, 14, 1]
+        );
+        assert_eq!(
+            result[4],
+            LedgerEntry {
+                id: 5,
+                matches: 0,
+                copies: 14,
+                sources: vec![(1, 1), (3, 4), (4, 8)],
+            }
+        );
+        assert_eq!(
+            to_json(&result[5..]),
+            "[{\"card\":6,\"matches\":0,\"copies\":1,\"from\":[]}]"
+        );
+    }
+}
@@ -1,58 +1,240 @@
-use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use scoring::{Doubling, Scoring};
+
+mod ledger;
+mod scoring;
+mod tally;
+mod validate;
+mod whatif;
 
 fn main() {
     let input = include_str!("./input.txt");
 
-    let part_1 = process_part_1(input);
-    let part_2 = process_part_2(input);
+    let games = match parse_games(input) {
+        Ok(games) => games,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    match std::env::args().nth(1).as_deref() {
+        Some("ledger") => {
+            let entries = ledger::ledger(&games);
+            match std::env::args().nth(2).as_deref() {
+                Some("--json") => println!("{}", ledger::to_json(&entries)),
+                _ => print!("{}", ledger::to_table(&entries)),
+            }
+            return;
+        }
+        Some("score") => {
+            let rule = std::env::args().nth(2).unwrap_or("doubling".to_string());
+            match rule.parse::<Box<dyn Scoring>>() {
+                Ok(rule) => match process_part_1(input, rule.as_ref()) {
+                    Ok(total) => println!("{total}"),
+                    Err(e) => eprintln!("{e}"),
+                },
+                Err(e) => eprintln!("{e}"),
+            }
+            return;
+        }
+        Some("tally") => {
+            let mut tally = tally::Tally::new(Box::new(Doubling));
+            for (i, line) in std::io::stdin().lines().enumerate() {
+                let line = line.expect("could not read card");
+                let game = match line.parse::<Game>() {
+                    Ok(game) => game,
+                    Err(e) => {
+                        eprintln!("{}", ParseError { line: i + 1, ..e });
+                        continue;
+                    }
+                };
+                match tally.push(&game) {
+                    Ok(()) => println!("{} {} {}", game.id, tally.part_1, tally.part_2),
+                    Err(e) => eprintln!("{e}"),
+                }
+            }
+            return;
+        }
+        Some("validate") => {
+            for issue in validate::validate(&games) {
+                println!("{issue}");
+            }
+            return;
+        }
+        Some("whatif") => {
+            match whatif::optimise(&games) {
+                Some(best) => println!("{}: {} -> {}", best.change, best.before, best.after),
+                None => println!("no single change increases the total"),
+            }
+            return;
+        }
+        _ => (),
+    }
+
+    let part_1 = process_part_1(input, &Doubling).unwrap();
+    let part_2 = process_part_2(input).unwrap();
 
     println!("{part_1}");
     println!("{part_2}");
 }
-#[derive(Debug)]
+
+#[derive(Debug, PartialEq)]
+enum ParseErrorKind {
+    MissingColon,
+    MissingCardId,
+    BadCardId(String),
+    MissingSeparator,
+    BadNumber(String),
+    DuplicateCardId(u32),
+}
+
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    line: usize,
+    column: usize,
+    kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::MissingColon => write!(f, "missing ':' after card id"),
+            ParseErrorKind::MissingCardId => write!(f, "missing card id"),
+            ParseErrorKind::BadCardId(id) => write!(f, "bad card id '{id}'"),
+            ParseErrorKind::MissingSeparator => write!(f, "missing '|' between number lists"),
+            ParseErrorKind::BadNumber(n) => write!(f, "bad number '{n}'"),
+            ParseErrorKind::DuplicateCardId(id) => write!(f, "card {id} is listed more than once"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ScoreError {
+    Parse(ParseError),
+    Overflow { line: usize, card: u32 },
+}
+
+impl From<ParseError> for ScoreError {
+    fn from(e: ParseError) -> Self {
+        ScoreError::Parse(e)
+    }
+}
+
+impl fmt::Display for ScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoreError::Parse(e) => write!(f, "{e}"),
+            ScoreError::Overflow { line, card } => {
+                write!(f, "line {line}: total overflows at card {card}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 struct Game {
+    id: u32,
     winning_numbers: Vec<u32>,
     numbers: Vec<u32>,
 }
 
+// whitespace separated tokens, paired with their byte offset in `s`
+fn tokens(s: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in s.char_indices().chain([(s.len(), ' ')]) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(from), true) => {
+                tokens.push((from, &s[from..i]));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    tokens
+}
+
+fn parse_numbers(s: &str, offset: usize) -> Result<Vec<u32>, ParseError> {
+    tokens(s)
+        .into_iter()
+        .map(|(column, n)| {
+            n.parse::<u32>().map_err(|_| ParseError {
+                line: 1,
+                column: offset + column + 1,
+                kind: ParseErrorKind::BadNumber(n.to_string()),
+            })
+        })
+        .collect()
+}
+
 impl FromStr for Game {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, s) = s.split_once(':').unwrap();
-        let (winning, numbers) = s.split_once('|').unwrap();
-        let winning_numbers = winning
-            .trim()
-            .split_whitespace()
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect();
-
-        let numbers = numbers
-            .trim()
-            .split_whitespace()
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect();
+        let error = |column: usize, kind| ParseError {
+            line: 1,
+            column: column + 1,
+            kind,
+        };
+
+        let Some((card, rest)) = s.split_once(':') else {
+            return Err(error(s.len(), ParseErrorKind::MissingColon));
+        };
+        let id = match tokens(card)[..] {
+            [(_, "Card"), (column, id)] => id
+                .parse::<u32>()
+                .map_err(|_| error(column, ParseErrorKind::BadCardId(id.to_string())))?,
+            _ => return Err(error(0, ParseErrorKind::MissingCardId)),
+        };
+
+        let offset = card.len() + 1;
+        let Some((winning, numbers)) = rest.split_once('|') else {
+            return Err(error(s.len(), ParseErrorKind::MissingSeparator));
+        };
+        let winning_numbers = parse_numbers(winning, offset)?;
+        let numbers = parse_numbers(numbers, offset + winning.len() + 1)?;
 
         Ok(Self {
+            id,
             winning_numbers,
             numbers,
         })
     }
 }
 
-impl Game {
-    fn score(self) -> u32 {
-        let winners = self.winner_count();
-
-        if winners == 0 {
-            return 0;
-        }
-
-        if winners == 1 {
-            return 1;
-        }
+fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    let mut ids = HashSet::new();
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let game = line
+                .parse::<Game>()
+                .map_err(|e| ParseError { line: i + 1, ..e })?;
+            if !ids.insert(game.id) {
+                return Err(ParseError {
+                    line: i + 1,
+                    column: tokens(line)[1].0 + 1,
+                    kind: ParseErrorKind::DuplicateCardId(game.id),
+                });
+            }
+            Ok(game)
+        })
+        .collect()
+}
 
-        2_u32.pow(winners - 1)
+impl Game {
+    fn score(&self, rule: &dyn Scoring) -> Option<u64> {
+        rule.score(self.winner_count())
     }
 
     fn winner_count(&self) -> u32 {
@@ -63,34 +245,72 @@ impl Game {
     }
 }
 
-fn cards(games: &Vec<u32>, index: u32) -> u32 {
-    let mut result = 0;
-    for i in 0..games[index as usize] {
-        result += cards(games, index + 1 + i)
+// The ids of the cards won by card `id`, whether or not those cards exist
+fn won(id: u32, winners: u32) -> Option<RangeInclusive<u32>> {
+    if winners == 0 {
+        return None;
     }
-    result + 1
+    let first = id.checked_add(1)?;
+    Some(first..=id.saturating_add(winners))
 }
 
-fn process_part_1(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| line.parse::<Game>().unwrap())
-        .map(|game| game.score())
-        .sum()
+// Copies cascade forward through cards taken in ascending id order. Copies won of a card wait in
+// `pending` until that card is added, and are dropped if its id is skipped, so copies of ids
+// missing from the input, including ids past the last card, never cascade.
+#[derive(Default)]
+struct Cascade {
+    // won card id -> (source card id, copies won from it)
+    pending: BTreeMap<u32, Vec<(u32, u64)>>,
 }
 
-fn process_part_2(input: &str) -> u32 {
-    let games: Vec<u32> = input
-        .lines()
-        .map(|line| line.parse::<Game>().unwrap().winner_count())
+impl Cascade {
+    // The copies held of card `id` and the cards they were won from. Ids must be ascending.
+    fn add(&mut self, id: u32, winners: u32) -> (u64, Vec<(u32, u64)>) {
+        self.pending = self.pending.split_off(&id);
+        let sources = self.pending.remove(&id).unwrap_or_default();
+        let held = 1 + sources.iter().map(|(_, copies)| copies).sum::<u64>();
+
+        if let Some(won) = won(id, winners) {
+            for won_id in won {
+                self.pending.entry(won_id).or_default().push((id, held));
+            }
+        }
+        (held, sources)
+    }
+}
+
+// Copies are keyed by card id, so the input can be in any order.
+fn copies(games: &[Game]) -> BTreeMap<u32, u64> {
+    let winners: BTreeMap<u32, u32> = games
+        .iter()
+        .map(|game| (game.id, game.winner_count()))
         .collect();
-    let mut total = 0;
+    let mut cascade = Cascade::default();
+
+    winners
+        .into_iter()
+        .map(|(id, count)| (id, cascade.add(id, count).0))
+        .collect()
+}
 
-    for (i, _) in games.iter().enumerate() {
-        total += cards(&games, i as u32);
+fn process_part_1(input: &str, rule: &dyn Scoring) -> Result<u64, ScoreError> {
+    let mut total = 0_u64;
+    for (i, game) in parse_games(input)?.iter().enumerate() {
+        total = game
+            .score(rule)
+            .and_then(|score| total.checked_add(score))
+            .ok_or(ScoreError::Overflow {
+                line: i + 1,
+                card: game.id,
+            })?;
     }
+    Ok(total)
+}
+
+fn process_part_2(input: &str) -> Result<u64, ParseError> {
+    let games = parse_games(input)?;
 
-    total
+    Ok(copies(&games).values().sum())
 }
 
 #[cfg(test)]
@@ -106,11 +326,27 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-        let result = process_part_1(input);
+        let result = process_part_1(input, &Doubling).unwrap();
 
         assert_eq!(result, 13);
     }
 
+    #[test]
+    fn part_1_overflow() {
+        let input = "Card 1: 1 2 | 1 2
+Card 2: 1 | 1";
+        let rule: Box<dyn Scoring> = "table:0,4000000000,18446744073709551615".parse().unwrap();
+
+        assert_eq!(
+            process_part_1(input.lines().next().unwrap(), rule.as_ref()),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            process_part_1(input, rule.as_ref()),
+            Err(ScoreError::Overflow { line: 2, card: 2 })
+        );
+    }
+
     #[test]
     fn part_2() {
         let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
@@ -120,8 +356,98 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-        let result = process_part_2(input);
+        let result = process_part_2(input).unwrap();
+
+        assert_eq!(result, 30);
+    }
+
+    #[test]
+    fn part_2_input() {
+        let input = include_str!("./input.txt");
+
+        let result = process_part_2(input).unwrap();
+
+        assert_eq!(result, 9881048);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let input = "Card 1: 41 48 | 83 86
+Card 2: 13 32 20 16 61  61 30";
+
+        assert_eq!(
+            parse_games(input).unwrap_err(),
+            ParseError {
+                line: 2,
+                column: 30,
+                kind: ParseErrorKind::MissingSeparator
+            }
+        );
+        assert_eq!(
+            "Card 3: 1 2x | 4".parse::<Game>().unwrap_err(),
+            ParseError {
+                line: 1,
+                column: 11,
+                kind: ParseErrorKind::BadNumber("2x".to_string())
+            }
+        );
+        assert_eq!(
+            "Card : 1 | 4".parse::<Game>().unwrap_err().kind,
+            ParseErrorKind::MissingCardId
+        );
+        assert_eq!(
+            "Card 3 1 | 4".parse::<Game>().unwrap_err().kind,
+            ParseErrorKind::MissingColon
+        );
+    }
+
+    #[test]
+    fn part_2_by_card_id() {
+        let input = "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1";
+
+        let result = process_part_2(input).unwrap();
 
         assert_eq!(result, 30);
     }
+
+    #[test]
+    fn part_2_gaps_and_overrun() {
+        // card 1 wins copies of 2 to 5, but only 3 and 5 exist
+        let input = "Card 1: 1 2 3 4 | 1 2 3 4
+Card 3: 1 | 1
+Card 5: 1 | 2";
+
+        let result = process_part_2(input).unwrap();
+
+        assert_eq!(result, 1 + 2 + 2);
+
+        let games = parse_games(input).unwrap();
+        let ledger: Vec<_> = ledger::ledger(&games).iter().map(|e| e.copies).collect();
+        assert_eq!(ledger, vec![1, 2, 2]);
+        let mut tally = tally::Tally::new(Box::new(Doubling));
+        for game in &games {
+            tally.push(game).unwrap();
+        }
+        assert_eq!(tally.part_2, result);
+    }
+
+    #[test]
+    fn duplicate_card_id() {
+        let input = "Card 1: 1 | 1
+Card  1: 2 | 2";
+
+        assert_eq!(
+            parse_games(input).unwrap_err(),
+            ParseError {
+                line: 2,
+                column: 7,
+                kind: ParseErrorKind::DuplicateCardId(1)
+            }
+        );
+    }
 }
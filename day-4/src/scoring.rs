@@ -0,0 +1,109 @@
+// Scores are None when they don't fit in a u64.
+pub trait Scoring {
+    fn score(&self, winners: u32) -> Option<u64>;
+}
+
+pub struct Doubling;
+
+impl Scoring for Doubling {
+    fn score(&self, winners: u32) -> Option<u64> {
+        if winners == 0 {
+            return Some(0);
+        }
+
+        2_u64.checked_pow(winners - 1)
+    }
+}
+
+pub struct Linear;
+
+impl Scoring for Linear {
+    fn score(&self, winners: u32) -> Option<u64> {
+        Some(winners.into())
+    }
+}
+
+pub struct Fibonacci;
+
+impl Scoring for Fibonacci {
+    fn score(&self, winners: u32) -> Option<u64> {
+        let (mut a, mut b) = (0_u64, Some(1_u64));
+        for _ in 0..winners {
+            let next = b.and_then(|b| b.checked_add(a));
+            a = b?;
+            b = next;
+        }
+        Some(a)
+    }
+}
+
+// Indexed by winner count, counts past the end of the table score the last entry.
+pub struct Table(pub Vec<u64>);
+
+impl Scoring for Table {
+    fn score(&self, winners: u32) -> Option<u64> {
+        self.0
+            .get(winners as usize)
+            .or(self.0.last())
+            .copied()
+            .or(Some(0))
+    }
+}
+
+impl std::str::FromStr for Box<dyn Scoring> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "doubling" => Ok(Box::new(Doubling)),
+            "linear" => Ok(Box::new(Linear)),
+            "fibonacci" => Ok(Box::new(Fibonacci)),
+            _ => {
+                let Some(table) = s.strip_prefix("table:") else {
+                    return Err(format!("unknown scoring rule '{s}'"));
+                };
+                let table = table
+                    .split(',')
+                    .map(|n| n.trim().parse::<u64>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| format!("bad scoring table '{table}'"))?;
+                Ok(Box::new(Table(table)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules() {
+        let rules: Vec<(Box<dyn Scoring>, [u64; 6])> = vec![
+            (Box::new(Doubling), [0, 1, 2, 4, 8, 16]),
+            (Box::new(Linear), [0, 1, 2, 3, 4, 5]),
+            (Box::new(Fibonacci), [0, 1, 1, 2, 3, 5]),
+            ("table:0,10,20".parse().unwrap(), [0, 10, 20, 20, 20, 20]),
+        ];
+
+        for (rule, expected) in rules {
+            let result: Vec<_> = (0..6).map(|n| rule.score(n).unwrap()).collect();
+
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(Doubling.score(64), Some(1 << 63));
+        assert_eq!(Doubling.score(65), None);
+        assert_eq!(Fibonacci.score(93), Some(12200160415121876738));
+        assert_eq!(Fibonacci.score(94), None);
+    }
+
+    #[test]
+    fn unknown_rule() {
+        assert!("squares".parse::<Box<dyn Scoring>>().is_err());
+        assert!("table:1,x".parse::<Box<dyn Scoring>>().is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{scoring::Scoring, Cascade, Game};
+
+// Running totals for cards that arrive one at a time in card id order.
+pub struct Tally {
+    rule: Box<dyn Scoring>,
+    last_id: Option<u32>,
+    cascade: Cascade,
+    pub part_1: u64,
+    pub part_2: u64,
+}
+
+impl Tally {
+    pub fn new(rule: Box<dyn Scoring>) -> Self {
+        Self {
+            rule,
+            last_id: None,
+            cascade: Cascade::default(),
+            part_1: 0,
+            part_2: 0,
+        }
+    }
+
+    pub fn push(&mut self, game: &Game) -> Result<(), String> {
+        if self.last_id.is_some_and(|id| game.id <= id) {
+            return Err(format!(
+                "card {} arrived after card {}",
+                game.id,
+                self.last_id.unwrap()
+            ));
+        }
+
+        let winners = game.winner_count();
+        let part_1 = self
+            .rule
+            .score(winners)
+            .and_then(|score| self.part_1.checked_add(score))
+            .ok_or(format!("total overflows at card {}", game.id))?;
+        self.last_id = Some(game.id);
+        let (held, _) = self.cascade.add(game.id, winners);
+
+        self.part_1 = part_1;
+        self.part_2 += held;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_games, process_part_1, process_part_2, scoring::Doubling};
+
+    #[test]
+    fn matches_batch_totals() {
+        let input = include_str!("./input.txt");
+        let mut tally = Tally::new(Box::new(Doubling));
+
+        for game in parse_games(input).unwrap() {
+            tally.push(&game).unwrap();
+        }
+
+        assert_eq!(tally.part_1, process_part_1(input, &Doubling).unwrap());
+        assert_eq!(tally.part_2, process_part_2(input).unwrap());
+    }
+
+    #[test]
+    fn running_totals() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83";
+        let games = parse_games(input).unwrap();
+        let mut tally = Tally::new(Box::new(Doubling));
+
+        let mut totals = vec![];
+        for game in &games[..4] {
+            tally.push(game).unwrap();
+            totals.push((tally.part_1, tally.part_2));
+        }
+
+        assert_eq!(totals, vec![(8, 1), (10, 3), (12, 7), (12, 13)]);
+        assert!(tally.push(&games[4]).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+
+use crate::Game;
+
+#[derive(Debug, PartialEq)]
+pub enum Issue {
+    DuplicateWinningNumber {
+        line: usize,
+        card: u32,
+        number: u32,
+    },
+    DuplicateNumber {
+        line: usize,
+        card: u32,
+        number: u32,
+    },
+    // the cards numbered `from` to `to` inclusive
+    MissingCards {
+        line: usize,
+        from: u32,
+        to: u32,
+    },
+    OutOfOrder {
+        line: usize,
+        card: u32,
+        previous: u32,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::DuplicateWinningNumber { line, card, number } => write!(
+                f,
+                "line {line}: card {card} lists winning number {number} more than once"
+            ),
+            Issue::DuplicateNumber { line, card, number } => {
+                write!(
+                    f,
+                    "line {line}: card {card} lists number {number} more than once"
+                )
+            }
+            Issue::MissingCards { line, from, to } if from == to => {
+                write!(f, "line {line}: card {from} is missing")
+            }
+            Issue::MissingCards { line, from, to } => {
+                write!(f, "line {line}: cards {from} to {to} are missing")
+            }
+            Issue::OutOfOrder {
+                line,
+                card,
+                previous,
+            } => write!(f, "line {line}: card {card} comes after card {previous}"),
+        }
+    }
+}
+
+fn duplicates(numbers: &[u32]) -> Vec<u32> {
+    let mut seen = vec![];
+    let mut duplicates = vec![];
+    for number in numbers {
+        if seen.contains(number) && !duplicates.contains(number) {
+            duplicates.push(*number);
+        }
+        seen.push(*number);
+    }
+    duplicates
+}
+
+pub fn validate(games: &[Game]) -> Vec<Issue> {
+    let mut issues = vec![];
+    let mut previous = 0;
+
+    for (i, game) in games.iter().enumerate() {
+        let line = i + 1;
+        for number in duplicates(&game.winning_numbers) {
+            issues.push(Issue::DuplicateWinningNumber {
+                line,
+                card: game.id,
+                number,
+            });
+        }
+        for number in duplicates(&game.numbers) {
+            issues.push(Issue::DuplicateNumber {
+                line,
+                card: game.id,
+                number,
+            });
+        }
+
+        if game.id <= previous {
+            issues.push(Issue::OutOfOrder {
+                line,
+                card: game.id,
+                previous,
+            });
+        } else {
+            if game.id > previous + 1 {
+                issues.push(Issue::MissingCards {
+                    line,
+                    from: previous + 1,
+                    to: game.id - 1,
+                });
+            }
+            previous = game.id;
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_games;
+
+    #[test]
+    fn issues() {
+        let input = "Card 1: 41 48 41 | 83 86
+Card 2: 13 32 | 61 61 61
+Card 5: 13 32 | 61 30
+Card 4: 13 32 | 61 30";
+        let games = parse_games(input).unwrap();
+
+        let result = validate(&games);
+
+        assert_eq!(
+            result,
+            vec![
+                Issue::DuplicateWinningNumber {
+                    line: 1,
+                    card: 1,
+                    number: 41
+                },
+                Issue::DuplicateNumber {
+                    line: 2,
+                    card: 2,
+                    number: 61
+                },
+                Issue::MissingCards {
+                    line: 3,
+                    from: 3,
+                    to: 4
+                },
+                Issue::OutOfOrder {
+                    line: 4,
+                    card: 4,
+                    previous: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn large_gap() {
+        let games = parse_games("Card 1: 1 | 2\nCard 4000000000: 1 | 2").unwrap();
+
+        let result = validate(&games);
+
+        assert_eq!(
+            result,
+            vec![Issue::MissingCards {
+                line: 2,
+                from: 2,
+                to: 3999999999
+            }]
+        );
+        assert_eq!(
+            result[0].to_string(),
+            "line 2: cards 2 to 3999999999 are missing"
+        );
+    }
+
+    #[test]
+    fn clean_input() {
+        let games = parse_games(include_str!("./input.txt")).unwrap();
+
+        assert_eq!(validate(&games), vec![]);
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+
+use crate::{copies, Game};
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    AddWinning { card: u32, number: u32 },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::AddWinning { card, number } => {
+                write!(f, "add winning number {number} to card {card}")
+            }
+        }
+    }
+}
+
+impl Change {
+    fn apply(&self, game: &mut Game) {
+        match self {
+            Change::AddWinning { number, .. } => game.winning_numbers.push(*number),
+        }
+    }
+}
+
+// Only changes that can add a match are worth trying, which means turning a drawn number that
+// isn't winning into a match. Changing that drawn number to a winning number gains exactly one
+// match, while adding it as a winning number gains one for every time it was drawn, and more
+// matches never mean fewer copies, so only the second form is tried.
+fn candidates(game: &Game) -> Vec<Change> {
+    let mut losing: Vec<u32> = game
+        .numbers
+        .iter()
+        .filter(|n| !game.winning_numbers.contains(n))
+        .copied()
+        .collect();
+    losing.sort();
+    losing.dedup();
+
+    losing
+        .into_iter()
+        .map(|number| Change::AddWinning {
+            card: game.id,
+            number,
+        })
+        .collect()
+}
+
+fn total(games: &[Game]) -> u64 {
+    copies(games).values().sum()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WhatIf {
+    pub change: Change,
+    pub before: u64,
+    pub after: u64,
+}
+
+// The first change, in card order, that gives the largest increase in the part 2 total.
+pub fn optimise(games: &[Game]) -> Option<WhatIf> {
+    let before = total(games);
+    let mut best: Option<WhatIf> = None;
+    let mut games = games.to_vec();
+
+    for i in 0..games.len() {
+        for change in candidates(&games[i]) {
+            let original = games[i].clone();
+            change.apply(&mut games[i]);
+            let after = total(&games);
+            games[i] = original;
+
+            if after > before && best.as_ref().is_none_or(|b| after > b.after) {
+                best = Some(WhatIf {
+                    change,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_games;
+
+    #[test]
+    fn best_change() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        let games = parse_games(input).unwrap();
+
+        let result = optimise(&games).unwrap();
+
+        assert_eq!(
+            result,
+            WhatIf {
+                change: Change::AddWinning {
+                    card: 5,
+                    number: 12
+                },
+                before: 30,
+                after: 44,
+            }
+        );
+    }
+
+    #[test]
+    fn repeated_drawn_number() {
+        // changing one 7 to a 5 would only win a copy of card 2
+        let games = parse_games("Card 1: 5 | 7 9 7\nCard 2: 1 | 2\nCard 3: 1 | 2").unwrap();
+
+        let result = optimise(&games).unwrap();
+
+        assert_eq!(
+            result,
+            WhatIf {
+                change: Change::AddWinning { card: 1, number: 7 },
+                before: 3,
+                after: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn no_improvement() {
+        let games = parse_games("Card 1: 1 2 | 1 2").unwrap();
+
+        assert_eq!(optimise(&games), None);
+    }
+}
@@ -0,0 +1,311 @@
+use std::{fmt, ops::Range, str::FromStr};
+
+use crate::{graph::CategoryGraph, Map};
+
+#[derive(Debug, PartialEq)]
+pub enum AlmanacError {
+    MissingSeeds,
+    NoSeeds,
+    EmptySeedRanges,
+    OddSeedCount(usize),
+    BadNumber { line: usize, value: String },
+    Overflow { line: usize },
+    WrongFieldCount { line: usize, count: usize },
+    BadHeader { line: usize },
+    MissingHeader { line: usize },
+    MissingRoute,
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::MissingSeeds => write!(f, "line 1: expected 'seeds:'"),
+            AlmanacError::NoSeeds => write!(f, "line 1: no seeds listed"),
+            AlmanacError::EmptySeedRanges => write!(f, "line 1: every seed range is empty"),
+            AlmanacError::OddSeedCount(count) => {
+                write!(f, "line 1: expected pairs of seeds, found {count} numbers")
+            }
+            AlmanacError::BadNumber { line, value } => {
+                write!(f, "line {line}: bad number '{value}'")
+            }
+            AlmanacError::Overflow { line } => {
+                write!(f, "line {line}: range runs past {}", i64::MAX)
+            }
+            AlmanacError::WrongFieldCount { line, count } => {
+                write!(f, "line {line}: expected 3 numbers, found {count}")
+            }
+            AlmanacError::BadHeader { line } => {
+                write!(f, "line {line}: expected '<category>-to-<category> map:'")
+            }
+            AlmanacError::MissingHeader { line } => {
+                write!(f, "line {line}: mapping comes before any map header")
+            }
+            AlmanacError::MissingRoute => write!(f, "no maps lead from seed to location"),
+        }
+    }
+}
+
+// A mapping line as written, so findings can point back at it.
+#[derive(Debug)]
+pub struct Mapping {
+    pub line: usize,
+    pub destination: i64,
+    pub source: i64,
+    pub length: i64,
+}
+
+#[derive(Debug)]
+pub struct Block {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub mappings: Vec<Mapping>,
+}
+
+// Parsed once, then queried seed to location with `maps` or location to seed with `reverse_maps`.
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    pub blocks: Vec<Block>,
+    pub graph: CategoryGraph,
+    pub maps: Vec<Map>,
+    pub reverse_maps: Vec<Map>,
+}
+
+impl Almanac {
+    // The mapping line numbered `line`.
+    pub fn mapping(&self, line: usize) -> Option<&Mapping> {
+        self.blocks
+            .iter()
+            .flat_map(|block| &block.mappings)
+            .find(|m| m.line == line)
+    }
+
+    pub fn seed_ranges(&self) -> Vec<Range<i64>> {
+        self.seeds.chunks(2).map(|a| a[0]..(a[0] + a[1])).collect()
+    }
+}
+
+// Numbers are never negative and every range ends at or before i64::MAX, so every value a map
+// can produce lies in 0..=i64::MAX and shifting by the difference of two values can't overflow.
+fn parse_numbers(s: &str, line: usize) -> Result<Vec<i64>, AlmanacError> {
+    s.split_whitespace()
+        .map(|num| match num.parse::<i64>() {
+            Ok(n) if n >= 0 => Ok(n),
+            _ => Err(AlmanacError::BadNumber {
+                line,
+                value: num.to_string(),
+            }),
+        })
+        .collect()
+}
+
+// The exclusive end of a range, which must fit in an i64.
+fn range_end(start: i64, length: i64, line: usize) -> Result<i64, AlmanacError> {
+    start
+        .checked_add(length)
+        .ok_or(AlmanacError::Overflow { line })
+}
+
+impl FromStr for Almanac {
+    type Err = AlmanacError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+        let seeds = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix("seeds:"))
+            .ok_or(AlmanacError::MissingSeeds)?;
+        let seeds = parse_numbers(seeds, 1)?;
+        if seeds.is_empty() {
+            return Err(AlmanacError::NoSeeds);
+        }
+        if seeds.len() % 2 != 0 {
+            return Err(AlmanacError::OddSeedCount(seeds.len()));
+        }
+        for pair in seeds.chunks(2) {
+            range_end(pair[0], pair[1], 1)?;
+        }
+
+        let mut blocks: Vec<Block> = vec![];
+        for (line, text) in lines {
+            if text.trim().is_empty() {
+                continue;
+            }
+            if let Some(header) = text.trim().strip_suffix(':') {
+                let (from, to) = header
+                    .trim_end_matches(" map")
+                    .split_once("-to-")
+                    .ok_or(AlmanacError::BadHeader { line })?;
+                blocks.push(Block {
+                    name: header.to_string(),
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    mappings: vec![],
+                });
+                continue;
+            }
+
+            let block = blocks
+                .last_mut()
+                .ok_or(AlmanacError::MissingHeader { line })?;
+            let nums = parse_numbers(text, line)?;
+            let [destination, source, length] = nums[..] else {
+                return Err(AlmanacError::WrongFieldCount {
+                    line,
+                    count: nums.len(),
+                });
+            };
+            range_end(source, length, line)?;
+            range_end(destination, length, line)?;
+            block.mappings.push(Mapping {
+                line,
+                destination,
+                source,
+                length,
+            });
+        }
+
+        let graph = CategoryGraph::new(
+            blocks
+                .iter()
+                .map(|block| {
+                    let mappings = block
+                        .mappings
+                        .iter()
+                        .map(|m| {
+                            (
+                                m.source..=(m.source + m.length - 1),
+                                m.destination - m.source,
+                                Some(m.line),
+                            )
+                        })
+                        .collect();
+                    (
+                        block.from.clone(),
+                        block.to.clone(),
+                        Map::with_lines(mappings),
+                    )
+                })
+                .collect(),
+        );
+        let maps = graph
+            .maps("seed", "location")
+            .ok_or(AlmanacError::MissingRoute)?;
+        let reverse_maps = graph
+            .maps("location", "seed")
+            .ok_or(AlmanacError::MissingRoute)?;
+
+        Ok(Self {
+            seeds,
+            blocks,
+            graph,
+            maps,
+            reverse_maps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("seed: 1 2", AlmanacError::MissingSeeds),
+            ("seeds: 1 2 3", AlmanacError::OddSeedCount(3)),
+            (
+                "seeds:\n\nseed-to-location map:\n1 2 3\n",
+                AlmanacError::NoSeeds,
+            ),
+            (
+                "seeds: 1 x",
+                AlmanacError::BadNumber {
+                    line: 1,
+                    value: "x".to_string(),
+                },
+            ),
+            (
+                "seeds: 1 2\n\nseed-to-location map:\n1 2\n",
+                AlmanacError::WrongFieldCount { line: 4, count: 2 },
+            ),
+            (
+                "seeds: 1 2\n\nseed-to-location map:\n1 2 3 4\n",
+                AlmanacError::WrongFieldCount { line: 4, count: 4 },
+            ),
+            (
+                "seeds: 9223372036854775800 100",
+                AlmanacError::Overflow { line: 1 },
+            ),
+            (
+                "seeds: 1 -2",
+                AlmanacError::BadNumber {
+                    line: 1,
+                    value: "-2".to_string(),
+                },
+            ),
+            (
+                "seeds: 1 2\n\nseed-to-location map:\n0 9223372036854775800 100\n",
+                AlmanacError::Overflow { line: 4 },
+            ),
+            (
+                "seeds: 1 2\n\nseed-to-location map:\n9223372036854775800 0 100\n",
+                AlmanacError::Overflow { line: 4 },
+            ),
+            (
+                "seeds: 1 2\n\nseed location map:\n1 2 3\n",
+                AlmanacError::BadHeader { line: 3 },
+            ),
+            (
+                "seeds: 1 2\n\n1 2 3\n",
+                AlmanacError::MissingHeader { line: 3 },
+            ),
+            (
+                "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n",
+                AlmanacError::MissingRoute,
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let result = input.parse::<Almanac>().unwrap_err();
+
+            assert_eq!(result, expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn forward_and_reverse() {
+        let almanac: Almanac = include_str!("./input.txt").parse().unwrap();
+
+        for seed in &almanac.seeds {
+            let location = almanac.maps.iter().fold(*seed, |v, map| map.map_value(v));
+            let result = almanac
+                .reverse_maps
+                .iter()
+                .fold(location, |v, map| map.map_value(v));
+
+            assert_eq!(result, *seed);
+        }
+    }
+
+    #[test]
+    fn ranges_up_to_the_limit() {
+        let input = "seeds: 9223372036854775000 807
+
+seed-to-location map:
+0 9223372036854775000 807
+9223372036854775000 0 807";
+
+        assert_eq!(crate::process_part_1(input), Ok(0));
+        assert_eq!(crate::process_part_2(input), Ok(0));
+
+        let almanac: Almanac = input.parse().unwrap();
+        assert_eq!(almanac.reverse_maps[0].map_value(806), i64::MAX - 1);
+        assert_eq!(
+            crate::compose::compose(&almanac.maps).map_value(i64::MAX - 1),
+            806
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use std::{hint::black_box, time::Instant};
+
+use crate::Map;
+
+// A map with `lines` mapping lines, each covering 50 values with a gap of 50 after it.
+fn generate(lines: usize) -> Map {
+    let mappings = (0..lines as i64)
+        .map(|i| {
+            let start = i * 100;
+            (start..=(start + 49), (i * 7919) % 1000 - 500)
+        })
+        .collect();
+    Map::new(mappings)
+}
+
+fn map_value_linear(map: &Map, value: i64) -> i64 {
+    for (range, shift_amount) in &map.mappings {
+        if range.contains(&value) {
+            return value + shift_amount;
+        }
+    }
+    value
+}
+
+pub fn run(lines: usize) {
+    let map = generate(lines);
+    let lookups = 100_000;
+    let values: Vec<i64> = (0..lookups)
+        .map(|i| (i * 2_654_435_761) % (lines as i64 * 100))
+        .collect();
+
+    let start = Instant::now();
+    let linear: i64 = values.iter().map(|v| map_value_linear(&map, *v)).sum();
+    let linear_time = start.elapsed();
+
+    let start = Instant::now();
+    let binary: i64 = values.iter().map(|v| map.map_value(*v)).sum();
+    let binary_time = start.elapsed();
+
+    assert_eq!(black_box(linear), black_box(binary));
+    println!("{lines} mapping lines, {lookups} lookups");
+    println!("linear scan:   {linear_time:?}");
+    println!("binary search: {binary_time:?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_search_matches_linear_scan() {
+        let map = generate(1000);
+
+        for value in (-10..100_010).step_by(7) {
+            assert_eq!(
+                map.map_value(value),
+                map_value_linear(&map, value),
+                "{}",
+                value
+            );
+        }
+    }
+}
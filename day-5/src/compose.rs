@@ -0,0 +1,176 @@
+use std::{fmt, ops::Range};
+
+use crate::Map;
+
+// A whole chain of maps folded into one function. Each segment is a source range and the amount
+// values in it are shifted by; the segments are sorted and cover every value from 0 up, and
+// neighbouring segments never share a shift so two equal functions compare equal.
+#[derive(Debug, PartialEq)]
+pub struct Composition {
+    pub segments: Vec<(Range<i64>, i64)>,
+}
+
+pub fn compose(maps: &[Map]) -> Composition {
+    let mut segments = vec![(0..i64::MAX, 0)];
+    for map in maps {
+        segments = segments
+            .into_iter()
+            .flat_map(|(range, offset)| {
+                map.segments((range.start + offset)..(range.end + offset))
+                    .into_iter()
+                    .map(move |(piece, shift_amount)| {
+                        (
+                            (piece.start - offset)..(piece.end - offset),
+                            offset + shift_amount,
+                        )
+                    })
+            })
+            .collect();
+    }
+    segments.sort_by_key(|(range, _)| range.start);
+
+    let mut merged: Vec<(Range<i64>, i64)> = vec![];
+    for (range, offset) in segments {
+        match merged.last_mut() {
+            Some((last, last_offset)) if *last_offset == offset && last.end == range.start => {
+                last.end = range.end;
+            }
+            _ => merged.push((range, offset)),
+        }
+    }
+    Composition { segments: merged }
+}
+
+impl Composition {
+    pub fn map_value(&self, value: i64) -> i64 {
+        let i = self
+            .segments
+            .partition_point(|(range, _)| range.end <= value);
+        match self.segments.get(i) {
+            Some((range, offset)) if range.contains(&value) => value + offset,
+            _ => value,
+        }
+    }
+
+    // Every seed interval that maps into `locations`, sorted and merged.
+    pub fn preimage(&self, locations: Range<i64>) -> Vec<Range<i64>> {
+        let mut result: Vec<Range<i64>> = self
+            .segments
+            .iter()
+            .filter_map(|(range, offset)| {
+                let start = (range.start + offset).max(locations.start);
+                let end = (range.end + offset).min(locations.end);
+                (start < end).then(|| (start - offset)..(end - offset))
+            })
+            .collect();
+        result.sort_by_key(|range| range.start);
+        merge(result)
+    }
+}
+
+fn merge(ranges: Vec<Range<i64>>) -> Vec<Range<i64>> {
+    let mut merged: Vec<Range<i64>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+// The parts of `ranges` that also lie in one of `seeds`.
+pub fn restrict(ranges: &[Range<i64>], seeds: &[Range<i64>]) -> Vec<Range<i64>> {
+    let mut result: Vec<Range<i64>> = ranges
+        .iter()
+        .flat_map(|range| {
+            seeds.iter().filter_map(|seed| {
+                let start = range.start.max(seed.start);
+                let end = range.end.min(seed.end);
+                (start < end).then_some(start..end)
+            })
+        })
+        .collect();
+    result.sort_by_key(|range| range.start);
+    merge(result)
+}
+
+impl fmt::Display for Composition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (range, offset) in &self.segments {
+            writeln!(f, "{}..{} {:+}", range.start, range.end, offset)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{almanac::Almanac, process_part_1, process_part_2};
+
+    #[test]
+    fn composed_chain() {
+        let input = include_str!("./input.txt");
+        let almanac: Almanac = input.parse().unwrap();
+        let maps = &almanac.maps;
+
+        let composition = compose(maps);
+
+        let result = almanac
+            .seeds
+            .iter()
+            .map(|seed| composition.map_value(*seed))
+            .min()
+            .unwrap();
+        assert_eq!(result, process_part_1(input).unwrap());
+
+        for value in (0..4_000_000_000).step_by(9_999_991) {
+            let expected = maps.iter().fold(value, |v, map| map.map_value(v));
+            assert_eq!(composition.map_value(value), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn equality() {
+        let swap = || Map::new(vec![(0..=9, 10), (10..=19, -10)]);
+        let split = Map::new(vec![(0..=4, 5), (5..=9, 5)]);
+
+        assert_eq!(
+            compose(&[swap(), swap()]),
+            Composition {
+                segments: vec![(0..i64::MAX, 0)]
+            }
+        );
+        assert_eq!(compose(&[split]), compose(&[Map::new(vec![(0..=9, 5)])]));
+    }
+
+    #[test]
+    fn preimages() {
+        let input = include_str!("./input.txt");
+        let almanac: Almanac = input.parse().unwrap();
+        let composition = compose(&almanac.maps);
+        let seeds = almanac.seed_ranges();
+        let lowest = process_part_2(input).unwrap();
+
+        let result = restrict(&composition.preimage(0..(lowest + 1)), &seeds);
+
+        assert!(!result.is_empty());
+        for range in result {
+            for seed in range {
+                assert_eq!(composition.map_value(seed), lowest);
+            }
+        }
+        assert_eq!(restrict(&composition.preimage(0..lowest), &seeds), vec![]);
+    }
+
+    #[test]
+    fn preimage_merges_pieces() {
+        let map = Map::new(vec![(0..=4, 5), (5..=9, -5)]);
+        let composition = compose(&[map]);
+
+        assert_eq!(composition.preimage(3..7), vec![0..2, 8..10]);
+        assert_eq!(composition.preimage(0..10), vec![0..10]);
+        assert_eq!(restrict(&[0..3, 5..10], &[2..4, 3..6]), vec![2..3, 5..6]);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+
+use crate::Map;
+
+// The almanac's maps keyed by the categories they convert between, so blocks can come in any
+// order and a route can be followed in either direction.
+#[derive(Debug)]
+pub struct CategoryGraph {
+    edges: Vec<(String, String, Map)>,
+}
+
+impl CategoryGraph {
+    pub fn new(edges: Vec<(String, String, Map)>) -> Self {
+        Self { edges }
+    }
+
+    // Breadth first search for the shortest route, as (edge, forwards) steps.
+    fn steps(&self, from: &str, to: &str) -> Option<Vec<(usize, bool)>> {
+        let mut queue = VecDeque::from([(from, vec![])]);
+        let mut seen = vec![from];
+        while let Some((category, steps)) = queue.pop_front() {
+            if category == to {
+                return Some(steps);
+            }
+            for (i, (source, destination, _)) in self.edges.iter().enumerate() {
+                let next = if source == category {
+                    (destination.as_str(), true)
+                } else if destination == category {
+                    (source.as_str(), false)
+                } else {
+                    continue;
+                };
+                if !seen.contains(&next.0) {
+                    seen.push(next.0);
+                    let mut steps = steps.clone();
+                    steps.push((i, next.1));
+                    queue.push_back((next.0, steps));
+                }
+            }
+        }
+        None
+    }
+
+    pub fn route(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut route = vec![from.to_string()];
+        for (i, forwards) in self.steps(from, to)? {
+            let (source, destination, _) = &self.edges[i];
+            route.push(if forwards { destination } else { source }.clone());
+        }
+        Some(route)
+    }
+
+    // The maps to apply in turn to convert a `from` value to a `to` value.
+    pub fn maps(&self, from: &str, to: &str) -> Option<Vec<Map>> {
+        let maps = self
+            .steps(from, to)?
+            .into_iter()
+            .map(|(i, forwards)| {
+                let map = &self.edges[i].2;
+                if forwards {
+                    map.clone()
+                } else {
+                    map.inverse()
+                }
+            })
+            .collect();
+        Some(maps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::almanac::Almanac;
+
+    const INPUT: &str = "seeds: 79 14 55 13
+
+humidity-to-location map:
+60 56 37
+56 93 4
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70";
+
+    fn convert(graph: &CategoryGraph, from: &str, to: &str, value: i64) -> i64 {
+        graph
+            .maps(from, to)
+            .unwrap()
+            .iter()
+            .fold(value, |v, map| map.map_value(v))
+    }
+
+    #[test]
+    fn routes() {
+        let graph = INPUT.parse::<Almanac>().unwrap().graph;
+
+        assert_eq!(
+            graph.route("soil", "humidity").unwrap(),
+            [
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity"
+            ]
+        );
+        assert_eq!(graph.route("seed", "seed").unwrap(), ["seed"]);
+        assert_eq!(graph.route("seed", "colour"), None);
+    }
+
+    #[test]
+    fn conversions() {
+        let graph = INPUT.parse::<Almanac>().unwrap().graph;
+
+        // seed 79 -> soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78
+        // -> humidity 78 -> location 82
+        assert_eq!(convert(&graph, "seed", "location", 79), 82);
+        assert_eq!(convert(&graph, "soil", "humidity", 81), 78);
+        assert_eq!(convert(&graph, "location", "seed", 82), 79);
+        assert_eq!(convert(&graph, "temperature", "soil", 78), 81);
+    }
+}
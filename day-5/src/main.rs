@@ -1,166 +1,353 @@
 use std::{
-    collections::HashMap,
     ops::{Range, RangeInclusive},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
+use almanac::{Almanac, AlmanacError};
+
+mod almanac;
+mod bench;
+mod compose;
+mod graph;
+mod search;
+mod trace;
+mod validate;
+
 fn main() {
     let input = include_str!("./input.txt");
 
-    let part_1 = process_part_1(input);
-
-    let part_2 = process_part_2(input);
+    let almanac: Almanac = match input.parse() {
+        Ok(almanac) => almanac,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    let part_1 = process_part_1(input).unwrap();
+
+    let part_2 = match std::env::args().nth(1).as_deref() {
+        Some("brute") => {
+            let args: Vec<String> = std::env::args().skip(2).collect();
+            let limit = match &args[..] {
+                [flag, n] if flag == "--limit" => {
+                    Some(n.parse().expect("limit should be a number"))
+                }
+                _ => None,
+            };
+            let cancel = Arc::new(AtomicBool::new(false));
+            let handler = cancel.clone();
+            ctrlc::set_handler(move || handler.store(true, Ordering::Relaxed))
+                .expect("could not set Ctrl-C handler");
+
+            let outcome = search::search(&almanac, limit, &cancel, |p| {
+                eprint!("\rchecked up to {} ({:.0}/s)", p.candidate, p.rate);
+            });
+            eprintln!();
+            match outcome {
+                search::Outcome::Found(location) => location,
+                search::Outcome::Cancelled { checked } => {
+                    eprintln!("cancelled after checking {checked} locations");
+                    return;
+                }
+                search::Outcome::LimitReached { checked } => {
+                    eprintln!("no seed found in the first {checked} locations");
+                    return;
+                }
+                search::Outcome::Exhausted => {
+                    eprintln!("no location maps back to a seed");
+                    return;
+                }
+            }
+        }
+        Some("compose") => {
+            let composition = compose::compose(&almanac.maps);
+            match std::env::args().nth(2) {
+                Some(path) => {
+                    let other = std::fs::read_to_string(path).expect("could not read almanac");
+                    let other: Almanac = match other.parse() {
+                        Ok(other) => other,
+                        Err(e) => {
+                            eprintln!("{e}");
+                            return;
+                        }
+                    };
+                    if composition == compose::compose(&other.maps) {
+                        println!("same");
+                    } else {
+                        println!("different");
+                    }
+                }
+                None => print!("{composition}"),
+            }
+            return;
+        }
+        Some("convert") => {
+            let mut args = std::env::args().skip(2);
+            let (Some(from), Some(to)) = (args.next(), args.next()) else {
+                eprintln!("usage: day-5 convert <from> <to> <value>...");
+                return;
+            };
+            let Some(maps) = almanac.graph.maps(&from, &to) else {
+                eprintln!("no route from {from} to {to}");
+                return;
+            };
+            println!("{}", almanac.graph.route(&from, &to).unwrap().join(" -> "));
+            for value in args {
+                let value: i64 = value.parse().expect("value should be a number");
+                let result = maps.iter().fold(value, |v, map| map.map_value(v));
+                println!("{value} {result}");
+            }
+            return;
+        }
+        Some("validate") => {
+            let almanac = match std::env::args().nth(2) {
+                Some(path) => {
+                    let other = std::fs::read_to_string(path).expect("could not read almanac");
+                    match other.parse() {
+                        Ok(other) => other,
+                        Err(e) => {
+                            eprintln!("{e}");
+                            return;
+                        }
+                    }
+                }
+                None => almanac,
+            };
+            for finding in validate::validate(&almanac) {
+                println!("{finding}");
+            }
+            return;
+        }
+        Some("preimage") => {
+            let args: Vec<String> = std::env::args().skip(2).collect();
+            let [start, end, ..] = &args[..] else {
+                eprintln!("usage: day-5 preimage <start> <end> [--seeds]");
+                return;
+            };
+            let locations = start.parse().expect("start should be a number")
+                ..end.parse().expect("end should be a number");
+            let composition = compose::compose(&almanac.maps);
+            let mut seeds = composition.preimage(locations);
+            if args.iter().any(|a| a == "--seeds") {
+                seeds = compose::restrict(&seeds, &almanac.seed_ranges());
+            }
+            for range in seeds {
+                println!("{}..{}", range.start, range.end);
+            }
+            return;
+        }
+        Some("trace") => {
+            let args: Vec<String> = std::env::args().skip(2).collect();
+            let mut seeds: Vec<i64> = args
+                .iter()
+                .filter(|a| *a != "--json")
+                .map(|a| a.parse().expect("seed should be a number"))
+                .collect();
+            if seeds.is_empty() {
+                seeds = almanac.seeds.clone();
+            }
+            let traces: Vec<_> = seeds
+                .iter()
+                .map(|seed| trace::trace(&almanac, *seed))
+                .collect();
+            if args.iter().any(|a| a == "--json") {
+                println!("{}", trace::to_json(&traces));
+            } else {
+                print!("{}", trace::to_table(&traces));
+            }
+            return;
+        }
+        Some("bench") => {
+            let lines = std::env::args().nth(2).map_or(5000, |n| n.parse().unwrap());
+            bench::run(lines);
+            return;
+        }
+        Some("query") => {
+            let composition = compose::compose(&almanac.maps);
+            for seed in std::env::args().skip(2) {
+                let seed: i64 = seed.parse().expect("seed should be a number");
+                println!("{seed} {}", composition.map_value(seed));
+            }
+            return;
+        }
+        _ => process_part_2(input).unwrap(),
+    };
 
     println!("{part_1}");
     println!("{part_2}");
 }
-#[derive(Debug)]
+// `mappings` is kept sorted by range start so a value can be found by binary search. `lines`
+// holds the input line each mapping came from, where it came from one.
+#[derive(Debug, Clone)]
 struct Map {
-    mappings: HashMap<RangeInclusive<i64>, i64>,
+    mappings: Vec<(RangeInclusive<i64>, i64)>,
+    lines: Vec<Option<usize>>,
 }
 
+// (source range, shift amount, input line)
+type LineMapping = (RangeInclusive<i64>, i64, Option<usize>);
+
 impl Map {
-    fn map_value(&self, value: i64) -> i64 {
-        for (range, shift_amount) in &self.mappings {
-            if range.contains(&value) {
-                return value + shift_amount;
+    fn new(mappings: Vec<(RangeInclusive<i64>, i64)>) -> Self {
+        Self::with_lines(
+            mappings
+                .into_iter()
+                .map(|(range, shift_amount)| (range, shift_amount, None))
+                .collect(),
+        )
+    }
+
+    // Where source ranges overlap the one given first wins, as it would checking each line in
+    // order, and is trimmed off the later ones so the stored ranges never overlap.
+    fn with_lines(mappings: Vec<LineMapping>) -> Self {
+        let mut claimed: Vec<LineMapping> = vec![];
+        for (range, shift_amount, line) in mappings {
+            let (mut start, end) = (*range.start(), *range.end());
+            let mut covered = range.is_empty();
+            let mut pieces = vec![];
+            let i = claimed.partition_point(|(other, _, _)| *other.end() < start);
+            for (other, _, _) in &claimed[i..] {
+                if *other.start() > end {
+                    break;
+                }
+                if *other.start() > start {
+                    pieces.push((start..=(other.start() - 1), shift_amount, line));
+                }
+                if *other.end() >= end {
+                    covered = true;
+                    break;
+                }
+                start = other.end() + 1;
+            }
+            if !covered {
+                pieces.push((start..=end, shift_amount, line));
             }
+            claimed.extend(pieces);
+            claimed.sort_by_key(|(range, _, _)| *range.start());
         }
+        let (mappings, lines) = claimed
+            .into_iter()
+            .map(|(range, shift_amount, line)| ((range, shift_amount), line))
+            .unzip();
+        Self { mappings, lines }
+    }
+
+    // The index of the mapping that applies to `value`, if any.
+    fn position(&self, value: i64) -> Option<usize> {
+        let i = self
+            .mappings
+            .partition_point(|(range, _)| *range.start() <= value)
+            .checked_sub(1)?;
+        self.mappings[i].0.contains(&value).then_some(i)
+    }
+
+    // The input line of the mapping that applies to `value`, if any.
+    fn line(&self, value: i64) -> Option<usize> {
+        self.lines[self.position(value)?]
+    }
+
+    // The mapping that applies to `value`, if any.
+    fn lookup(&self, value: i64) -> Option<&(RangeInclusive<i64>, i64)> {
+        self.position(value).map(|i| &self.mappings[i])
+    }
+
+    fn map_value(&self, value: i64) -> i64 {
         value
+            + self
+                .lookup(value)
+                .map_or(0, |(_, shift_amount)| *shift_amount)
     }
 
-    fn map_value_rev(&self, value: i64) -> i64 {
-        for (range, shift_amount) in &self.mappings {
-            if range.contains(&value) {
-                return value - shift_amount;
+    // Splits `range` into the pieces covered by each mapping, paired with the amount each piece
+    // is shifted by. Pieces that no mapping covers pass through with a shift of 0.
+    fn segments(&self, range: Range<i64>) -> Vec<(Range<i64>, i64)> {
+        if range.is_empty() {
+            return vec![];
+        }
+        let mut result = vec![];
+        let mut start = range.start;
+        for (source, shift_amount) in &self.mappings {
+            let (from, to) = (*source.start(), *source.end() + 1);
+            if to <= start {
+                continue;
+            }
+            if from >= range.end {
+                break;
             }
+            if from > start {
+                result.push((start..from, 0));
+                start = from;
+            }
+            let end = to.min(range.end);
+            result.push((start..end, *shift_amount));
+            start = end;
         }
-        value
+        if start < range.end {
+            result.push((start..range.end, 0));
+        }
+        result
     }
-}
-
-fn get_seeds(input: &str) -> Vec<i64> {
-    input
-        .lines()
-        .next()
-        .unwrap()
-        .split_once(':')
-        .unwrap()
-        .1
-        .trim()
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect()
-}
-
-fn get_seed_ranges(input: &str) -> Vec<Range<i64>> {
-    let seeds: Vec<i64> = input
-        .lines()
-        .next()
-        .unwrap()
-        .split_once(':')
-        .unwrap()
-        .1
-        .trim()
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    seeds
-        .windows(2)
-        .step_by(2)
-        .map(|a| a[0]..(a[0] + a[1]))
-        .collect()
-}
 
-fn get_maps(input: &str) -> Vec<Map> {
-    input
-        .split("\n\n")
-        .skip(1)
-        .map(|line| {
-            let (_, raw_map) = line.split_once(':').unwrap();
-            let mappings: HashMap<_, _> = raw_map
-                .split("\n")
-                .filter(|l| !l.is_empty())
-                .map(|nums| {
-                    let nums: Vec<_> = nums
-                        .split_whitespace()
-                        .map(|num| num.parse::<i64>().unwrap())
-                        .collect();
-                    let range = nums[1]..=(nums[1] + nums[2] - 1);
-                    let shift_amount = nums[0] - nums[1];
-                    (range, shift_amount)
-                })
-                .collect();
-            Map { mappings }
-        })
-        .collect()
-}
+    fn map_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        self.segments(range)
+            .into_iter()
+            .map(|(range, shift_amount)| (range.start + shift_amount)..(range.end + shift_amount))
+            .collect()
+    }
 
-fn get_maps_rev(input: &str) -> Vec<Map> {
-    input
-        .split("\n\n")
-        .skip(1)
-        .map(|line| {
-            let (_, raw_map) = line.split_once(':').unwrap();
-            let mappings: HashMap<_, _> = raw_map
-                .split("\n")
-                .filter(|l| !l.is_empty())
-                .map(|nums| {
-                    let nums: Vec<_> = nums
-                        .split_whitespace()
-                        .map(|num| num.parse::<i64>().unwrap())
-                        .collect();
-                    let range = nums[0]..=(nums[0] + nums[2] - 1);
-                    let shift_amount = nums[0] - nums[1];
-                    (range, shift_amount)
+    fn inverse(&self) -> Map {
+        Map::with_lines(
+            self.mappings
+                .iter()
+                .zip(&self.lines)
+                .map(|((range, shift_amount), line)| {
+                    let destination = (range.start() + shift_amount)..=(range.end() + shift_amount);
+                    (destination, -shift_amount, *line)
                 })
-                .collect();
-            Map { mappings }
-        })
-        .collect()
+                .collect(),
+        )
+    }
 }
 
-fn process_part_1(input: &str) -> i64 {
-    // get seed numbers from line 1
-    let seeds = get_seeds(input);
+fn process_part_1(input: &str) -> Result<i64, AlmanacError> {
+    let almanac: Almanac = input.parse()?;
 
-    // parse remaining input into maps
-    let maps = get_maps(input);
     // iterate over seeds
     let mut results = vec![];
-    for seed in seeds {
-        let mut result = seed;
-        for map in &maps {
+    for seed in &almanac.seeds {
+        let mut result = *seed;
+        for map in &almanac.maps {
             result = map.map_value(result);
         }
         results.push(result);
     }
     // call min on resulting values
-    *results.iter().min().unwrap()
+    Ok(*results.iter().min().unwrap())
 }
 
-fn ranges_contain_value(ranges: &Vec<Range<i64>>, value: i64) -> bool {
+fn ranges_contain_value(ranges: &[Range<i64>], value: i64) -> bool {
     ranges.iter().any(|range| range.contains(&value))
 }
 
-fn process_part_2(input: &str) -> i64 {
-    let seed_ranges = get_seed_ranges(input);
-    // parse remaining input into maps
-    let maps = get_maps_rev(input);
-
-    let mut found = false;
-    let mut value = 0;
-    while !found {
-        let mut result = value;
-        for map in maps.iter().rev() {
-            result = map.map_value_rev(result);
-        }
-        found = ranges_contain_value(&seed_ranges, result);
-        if !found {
-            value += 1;
-        }
+fn process_part_2(input: &str) -> Result<i64, AlmanacError> {
+    let almanac: Almanac = input.parse()?;
+
+    let mut ranges = almanac.seed_ranges();
+    for map in &almanac.maps {
+        ranges = ranges
+            .into_iter()
+            .flat_map(|range| map.map_range(range))
+            .collect();
     }
-    value
+    ranges
+        .iter()
+        .map(|range| range.start)
+        .min()
+        .ok_or(AlmanacError::EmptySeedRanges)
 }
 
 #[cfg(test)]
@@ -168,9 +355,7 @@ mod tests {
     use super::*;
     #[test]
     fn mapping() {
-        let map = Map {
-            mappings: [(98..=99, 50 - 98), (50..=97, 52 - 50)].into(),
-        };
+        let map = Map::new(vec![(98..=99, 50 - 98), (50..=97, 52 - 50)]);
 
         let test_values = [
             (0, 0),
@@ -192,6 +377,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn range_mapping() {
+        let map = Map::new(vec![(98..=99, 50 - 98), (50..=97, 52 - 50)]);
+
+        let test_ranges = [
+            (40..60, vec![40..50, 52..62]),
+            (90..100, vec![92..100, 50..52]),
+            (95..110, vec![97..100, 50..52, 100..110]),
+        ];
+
+        for (test, expected) in test_ranges {
+            let result = map.map_range(test.clone());
+
+            assert_eq!(result, expected, "{:?}", test);
+        }
+    }
+
+    #[test]
+    fn empty_seed_ranges() {
+        let map = Map::new(vec![(0..=9, 0)]);
+
+        assert_eq!(map.segments(5..5), vec![]);
+        assert_eq!(map.map_range(5..5), vec![]);
+
+        let input = "seeds: 5 0 50 1
+
+seed-to-location map:
+0 0 10";
+
+        assert_eq!(process_part_2(input), Ok(50));
+        assert_eq!(
+            process_part_2(&input.replace("5 0 50 1", "50 0")),
+            Err(AlmanacError::EmptySeedRanges)
+        );
+    }
+
+    #[test]
+    fn overlapping_mappings() {
+        let map = Map::new(vec![(0..=9, 100), (5..=7, 200), (8..=14, 300)]);
+
+        assert_eq!(map.map_value(6), 106);
+        assert_eq!(map.map_value(8), 108);
+        assert_eq!(map.map_value(12), 312);
+        assert_eq!(
+            map.segments(0..20),
+            vec![(0..10, 100), (10..15, 300), (15..20, 0)]
+        );
+
+        let input = "seeds: 6 1 8 1
+
+seed-to-location map:
+100 0 10
+205 5 3";
+
+        assert_eq!(process_part_1(input), Ok(101));
+        assert_eq!(process_part_2(input), Ok(106));
+        let almanac: Almanac = input.parse().unwrap();
+        assert_eq!(compose::compose(&almanac.maps).map_value(6), 106);
+    }
+
     #[test]
     fn part_1() {
         let input = "seeds: 79 14 55 13
@@ -228,7 +473,7 @@ humidity-to-location map:
 60 56 37
 56 93 4";
 
-        let result = process_part_1(input);
+        let result = process_part_1(input).unwrap();
 
         assert_eq!(result, 35);
     }
@@ -269,8 +514,22 @@ humidity-to-location map:
 60 56 37
 56 93 4";
 
-        let result = process_part_2(input);
+        let result = process_part_2(input).unwrap();
 
         assert_eq!(result, 46);
+
+        let almanac: Almanac = input.parse().unwrap();
+        let result = search::search(&almanac, None, &AtomicBool::new(false), |_| {});
+
+        assert_eq!(result, search::Outcome::Found(46));
+    }
+
+    #[test]
+    fn part_2_input() {
+        let input = include_str!("./input.txt");
+
+        let result = process_part_2(input).unwrap();
+
+        assert_eq!(result, 9622622);
     }
 }
@@ -0,0 +1,90 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use rayon::prelude::*;
+
+use crate::{almanac::Almanac, ranges_contain_value};
+
+// Candidates are checked in chunks, split across threads; progress, cancellation and the limit
+// are checked between chunks.
+const CHUNK: i64 = 1 << 20;
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Found(i64),
+    Cancelled { checked: i64 },
+    LimitReached { checked: i64 },
+    Exhausted,
+}
+
+pub struct Progress {
+    pub candidate: i64,
+    pub rate: f64,
+}
+
+// Counts up from location 0 until a location maps back to one of the almanac's seed ranges.
+pub fn search(
+    almanac: &Almanac,
+    limit: Option<i64>,
+    cancel: &AtomicBool,
+    progress: impl Fn(Progress),
+) -> Outcome {
+    let seed_ranges = almanac.seed_ranges();
+    let is_seed = |location: i64| {
+        let seed = almanac
+            .reverse_maps
+            .iter()
+            .fold(location, |v, map| map.map_value(v));
+        ranges_contain_value(&seed_ranges, seed)
+    };
+
+    let start = Instant::now();
+    let end = limit.unwrap_or(i64::MAX);
+    let mut candidate = 0;
+    while candidate < end {
+        if cancel.load(Ordering::Relaxed) {
+            return Outcome::Cancelled { checked: candidate };
+        }
+        let chunk_end = candidate.saturating_add(CHUNK).min(end);
+        if let Some(location) = (candidate..chunk_end)
+            .into_par_iter()
+            .find_first(|l| is_seed(*l))
+        {
+            return Outcome::Found(location);
+        }
+        candidate = chunk_end;
+        progress(Progress {
+            candidate,
+            rate: candidate as f64 / start.elapsed().as_secs_f64(),
+        });
+    }
+
+    match limit {
+        Some(_) => Outcome::LimitReached { checked: candidate },
+        None => Outcome::Exhausted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_at_limit_or_cancel() {
+        let almanac: Almanac = include_str!("./input.txt").parse().unwrap();
+
+        let result = search(&almanac, Some(1_500_000), &AtomicBool::new(false), |_| {});
+        assert_eq!(result, Outcome::LimitReached { checked: 1_500_000 });
+
+        let result = search(&almanac, None, &AtomicBool::new(true), |_| {});
+        assert_eq!(result, Outcome::Cancelled { checked: 0 });
+
+        let cancel = AtomicBool::new(false);
+        let result = search(&almanac, None, &cancel, |_| {
+            cancel.store(true, Ordering::Relaxed)
+        });
+        assert_eq!(result, Outcome::Cancelled { checked: CHUNK });
+    }
+}
@@ -0,0 +1,160 @@
+use crate::almanac::Almanac;
+
+#[derive(Debug, PartialEq)]
+pub struct Stage {
+    pub category: String,
+    pub value: i64,
+    // the mapping line that moved the value here, as (line, destination, source, length), or
+    // None if the value passed through unchanged
+    pub mapping: Option<(usize, i64, i64, i64)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Trace {
+    pub seed: i64,
+    pub stages: Vec<Stage>,
+}
+
+pub fn trace(almanac: &Almanac, seed: i64) -> Trace {
+    let route = almanac.graph.route("seed", "location").unwrap();
+    let mut value = seed;
+    let stages = route[1..]
+        .iter()
+        .zip(&almanac.maps)
+        .map(|(category, map)| {
+            let mapping = map
+                .line(value)
+                .and_then(|line| almanac.mapping(line))
+                .map(|m| (m.line, m.destination, m.source, m.length));
+            value = map.map_value(value);
+            Stage {
+                category: category.clone(),
+                value,
+                mapping,
+            }
+        })
+        .collect();
+    Trace { seed, stages }
+}
+
+pub fn to_table(traces: &[Trace]) -> String {
+    let mut table = String::new();
+    for trace in traces {
+        table.push_str(&format!("seed {}\n", trace.seed));
+        for stage in &trace.stages {
+            let via = match stage.mapping {
+                Some((line, destination, source, length)) => {
+                    format!("line {line}: {destination} {source} {length}")
+                }
+                None => "unchanged".to_string(),
+            };
+            table.push_str(&format!(
+                "  {:<12} {:>12}  {via}\n",
+                stage.category, stage.value
+            ));
+        }
+    }
+    table
+}
+
+pub fn to_json(traces: &[Trace]) -> String {
+    let traces: Vec<_> = traces
+        .iter()
+        .map(|trace| {
+            let stages: Vec<_> = trace
+                .stages
+                .iter()
+                .map(|stage| {
+                    let mapping = match stage.mapping {
+                        Some((line, destination, source, length)) => {
+                            format!("{{\"line\":{line},\"destination\":{destination},\"source\":{source},\"length\":{length}}}")
+                        }
+                        None => "null".to_string(),
+                    };
+                    format!(
+                        "{{\"category\":\"{}\",\"value\":{},\"mapping\":{mapping}}}",
+                        stage.category, stage.value
+                    )
+                })
+                .collect();
+            format!(
+                "{{\"seed\":{},\"stages\":[{}]}}",
+                trace.seed,
+                stages.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", traces.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_trace() {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+        let almanac: Almanac = input.parse().unwrap();
+
+        let result = trace(&almanac, 79);
+
+        let values: Vec<_> = result.stages.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![81, 81, 81, 74, 78, 78, 82]);
+        assert_eq!(result.stages[0].mapping, Some((5, 52, 50, 48)));
+        assert_eq!(result.stages[1].mapping, None);
+        assert_eq!(result.stages[6].category, "location");
+        assert!(to_json(&[result]).starts_with(
+            "[{\"seed\":79,\"stages\":[{\"category\":\"soil\",\"value\":81,\"mapping\":{\"line\":5,\"destination\":52,\"source\":50,\"length\":48}},"
+        ));
+    }
+
+    #[test]
+    fn overlapping_lines() {
+        let input = "seeds: 2 1
+
+seed-to-location map:
+100 5 10
+300 0 10";
+        let almanac: Almanac = input.parse().unwrap();
+
+        let result = trace(&almanac, 2);
+        assert_eq!(result.stages[0].value, 302);
+        assert_eq!(result.stages[0].mapping, Some((5, 300, 0, 10)));
+
+        let result = trace(&almanac, 7);
+        assert_eq!(result.stages[0].value, 102);
+        assert_eq!(result.stages[0].mapping, Some((4, 100, 5, 10)));
+    }
+}
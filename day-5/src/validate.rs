@@ -0,0 +1,210 @@
+use std::{fmt, ops::Range};
+
+use crate::almanac::Almanac;
+
+#[derive(Debug, PartialEq)]
+pub enum FindingKind {
+    ZeroLength,
+    OverlappingSource { other_line: usize },
+    CollidingDestination { other_line: usize },
+    // the destination covers values that no source range maps, so it collides with those values
+    // passing through unchanged
+    CollidesWithPassThrough { values: Range<i64> },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    pub block: String,
+    pub line: usize,
+    pub kind: FindingKind,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} ({}): ", self.line, self.block)?;
+        match &self.kind {
+            FindingKind::ZeroLength => write!(f, "range has zero length"),
+            FindingKind::OverlappingSource { other_line } => {
+                write!(f, "source range overlaps line {other_line}")
+            }
+            FindingKind::CollidingDestination { other_line } => {
+                write!(f, "destination range overlaps line {other_line}")
+            }
+            FindingKind::CollidesWithPassThrough { values } => write!(
+                f,
+                "destination {}..{} is also reached by values passing through",
+                values.start, values.end
+            ),
+        }
+    }
+}
+
+// (line, source, destination)
+type Line = (usize, Range<i64>, Range<i64>);
+
+// Pairs of (line, earlier line) whose ranges overlap.
+fn overlaps(ranges: &mut [(usize, Range<i64>)]) -> Vec<(usize, usize)> {
+    ranges.sort_by_key(|(_, range)| range.start);
+    let mut result = vec![];
+    let mut furthest: Option<(usize, i64)> = None;
+    for (line, range) in ranges.iter() {
+        if let Some((other_line, end)) = furthest {
+            if range.start < end {
+                result.push((*line, other_line));
+            }
+        }
+        if furthest.is_none_or(|(_, end)| range.end > end) {
+            furthest = Some((*line, range.end));
+        }
+    }
+    result.sort();
+    result
+}
+
+// The parts of `range` that none of `covered` (sorted by start) contain.
+fn uncovered(range: &Range<i64>, covered: &[Range<i64>]) -> Vec<Range<i64>> {
+    let mut result = vec![];
+    let mut start = range.start;
+    for c in covered {
+        if c.end <= start {
+            continue;
+        }
+        if c.start >= range.end {
+            break;
+        }
+        if c.start > start {
+            result.push(start..c.start);
+        }
+        start = start.max(c.end);
+    }
+    if start < range.end {
+        result.push(start..range.end);
+    }
+    result
+}
+
+fn check_block(block: &str, lines: &[Line]) -> Vec<Finding> {
+    let finding = |line, kind| Finding {
+        block: block.to_string(),
+        line,
+        kind,
+    };
+    let mut findings = vec![];
+
+    for (line, source, _) in lines {
+        if source.is_empty() {
+            findings.push(finding(*line, FindingKind::ZeroLength));
+        }
+    }
+    let lines: Vec<_> = lines.iter().filter(|(_, s, _)| !s.is_empty()).collect();
+
+    let mut sources: Vec<_> = lines.iter().map(|(l, s, _)| (*l, s.clone())).collect();
+    for (line, other_line) in overlaps(&mut sources) {
+        findings.push(finding(line, FindingKind::OverlappingSource { other_line }));
+    }
+    let mut destinations: Vec<_> = lines.iter().map(|(l, _, d)| (*l, d.clone())).collect();
+    for (line, other_line) in overlaps(&mut destinations) {
+        findings.push(finding(
+            line,
+            FindingKind::CollidingDestination { other_line },
+        ));
+    }
+
+    let sources: Vec<_> = sources.into_iter().map(|(_, s)| s).collect();
+    for (line, _, destination) in &lines {
+        for values in uncovered(destination, &sources) {
+            findings.push(finding(
+                *line,
+                FindingKind::CollidesWithPassThrough { values },
+            ));
+        }
+    }
+    findings
+}
+
+pub fn validate(almanac: &Almanac) -> Vec<Finding> {
+    almanac
+        .blocks
+        .iter()
+        .flat_map(|block| {
+            let lines: Vec<Line> = block
+                .mappings
+                .iter()
+                .map(|m| {
+                    (
+                        m.line,
+                        m.source..(m.source + m.length),
+                        m.destination..(m.destination + m.length),
+                    )
+                })
+                .collect();
+            check_block(&block.name, &lines)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn findings() {
+        let input = "seeds: 1 2
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-location map:
+0 15 37
+10 30 5
+0 100 0
+200 0 5";
+
+        let result = validate(&input.parse().unwrap());
+
+        let block = "soil-to-location map";
+        assert_eq!(
+            result,
+            vec![
+                Finding {
+                    block: block.to_string(),
+                    line: 10,
+                    kind: FindingKind::ZeroLength
+                },
+                Finding {
+                    block: block.to_string(),
+                    line: 9,
+                    kind: FindingKind::OverlappingSource { other_line: 8 }
+                },
+                Finding {
+                    block: block.to_string(),
+                    line: 9,
+                    kind: FindingKind::CollidingDestination { other_line: 8 }
+                },
+                Finding {
+                    block: block.to_string(),
+                    line: 8,
+                    kind: FindingKind::CollidesWithPassThrough { values: 5..15 }
+                },
+                Finding {
+                    block: block.to_string(),
+                    line: 9,
+                    kind: FindingKind::CollidesWithPassThrough { values: 10..15 }
+                },
+                Finding {
+                    block: block.to_string(),
+                    line: 11,
+                    kind: FindingKind::CollidesWithPassThrough { values: 200..205 }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn clean_input() {
+        let almanac: Almanac = include_str!("./input.txt").parse().unwrap();
+
+        assert_eq!(validate(&almanac), vec![]);
+    }
+}
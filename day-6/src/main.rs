@@ -1,8 +1,77 @@
+use std::ops::RangeInclusive;
+
+use num_bigint::BigUint;
+
+use model::{RaceModel, Standard};
+use wide::WideRace;
+
+mod model;
+mod report;
+mod tournament;
+mod wide;
+
 fn main() {
     let input = include_str!("./input.txt");
 
-    let part_1 = process_part_1(input);
-    let part_2 = process_part_2(input);
+    let model = match std::env::args().nth(1).as_deref() {
+        Some("report") => {
+            let races = match get_races(input) {
+                Ok(races) => races,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            };
+            let reports: Vec<_> = races.iter().map(report::report).collect();
+            match std::env::args().nth(2).as_deref() {
+                Some("--json") => println!("{}", report::to_json(&reports)),
+                _ => print!("{}", report::to_table(&reports)),
+            }
+            return;
+        }
+        Some("tournament") => {
+            let races = match get_races(input) {
+                Ok(races) => races,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            };
+            let budget = match std::env::args().nth(2).map(|b| b.parse::<u64>()) {
+                Some(Ok(budget)) => budget,
+                Some(Err(_)) => {
+                    eprintln!("usage: tournament <budget> [--margin]");
+                    std::process::exit(1);
+                }
+                None => races
+                    .iter()
+                    .fold(0u64, |total, r| total.saturating_add(r.time)),
+            };
+            let goal = match std::env::args().nth(3).as_deref() {
+                Some("--margin") => tournament::Goal::Margin,
+                _ => tournament::Goal::Wins,
+            };
+            match tournament::optimise(&races, budget, goal) {
+                Ok(plan) => print!("{plan}"),
+                Err(e) => eprintln!("{e}"),
+            }
+            return;
+        }
+        Some("model") => {
+            let spec = std::env::args().nth(2).unwrap_or("standard".to_string());
+            match spec.parse::<Box<dyn RaceModel>>() {
+                Ok(model) => model,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ => Box::new(Standard),
+    };
+
+    let part_1 = process_part_1(input, model.as_ref());
+    let part_2 = process_part_2(input, model.as_ref());
 
     println!("{part_1}");
     println!("{part_2}");
@@ -14,77 +83,89 @@ struct Race {
     time: u64,
 }
 
-fn get_numbers(line: &str) -> Vec<u64> {
+fn get_numbers(line: &str) -> Vec<BigUint> {
     line.split_once(':')
         .unwrap()
         .1
-        .trim()
         .split_whitespace()
         .map(|a| a.parse().unwrap())
         .collect()
 }
 
-fn get_number(line: &str) -> u64 {
-    let nums: Vec<_> = line
-        .split_once(':')
-        .unwrap()
-        .1
-        .trim()
-        .split_whitespace()
-        .collect();
+fn get_number(line: &str) -> BigUint {
+    let nums: Vec<_> = line.split_once(':').unwrap().1.split_whitespace().collect();
 
     nums.join("").parse().unwrap()
 }
 
-fn get_races(input: &str) -> Vec<Race> {
+fn get_wide_races(input: &str) -> Vec<WideRace> {
     let mut lines = input.lines();
     let times = get_numbers(lines.next().unwrap());
     let distances = get_numbers(lines.next().unwrap());
 
     distances
         .into_iter()
-        .zip(times.into_iter())
-        .map(|(distance, time)| Race { distance, time })
+        .zip(times)
+        .map(|(distance, time)| WideRace { distance, time })
         .collect()
 }
 
-fn get_race(input: &str) -> Race {
+// Races for the report and tournament, which work in fixed width.
+fn get_races(input: &str) -> Result<Vec<Race>, String> {
+    get_wide_races(input)
+        .iter()
+        .enumerate()
+        .map(
+            |(i, race)| match (u64::try_from(&race.time), u64::try_from(&race.distance)) {
+                (Ok(time), Ok(distance)) => Ok(Race { distance, time }),
+                _ => Err(format!("race {} is too long for a u64", i + 1)),
+            },
+        )
+        .collect()
+}
+
+fn get_race(input: &str) -> WideRace {
     let mut lines = input.lines();
-    let time = get_number(lines.next().unwrap()).to_owned();
-    let distance = get_number(lines.next().unwrap()).to_owned();
+    let time = get_number(lines.next().unwrap());
+    let distance = get_number(lines.next().unwrap());
+
+    WideRace { time, distance }
+}
 
-    Race { time, distance }
+impl Race {
+    // Holding for h wins when h * (time - h) > distance. The winning holds are the integers
+    // strictly between the roots of h^2 - time * h + distance, and are symmetric about time / 2,
+    // so only the first one needs finding.
+    fn window(&self) -> Option<RangeInclusive<u64>> {
+        let time = u128::from(self.time);
+        let distance = u128::from(self.distance);
+        let wins = |hold: u128| hold * (time - hold) > distance;
+
+        let discriminant = (time * time).checked_sub(4 * distance)?;
+        let mut first = (time - discriminant.isqrt()) / 2;
+        while first > 0 && wins(first - 1) {
+            first -= 1;
+        }
+        while first <= time / 2 && !wins(first) {
+            first += 1;
+        }
+        if first > time / 2 {
+            return None;
+        }
+        Some(first as u64..=(time - first) as u64)
+    }
 }
 
-fn process_part_1(input: &str) -> u64 {
-    let races = get_races(input);
+fn process_part_1(input: &str, model: &dyn RaceModel) -> BigUint {
+    let races = get_wide_races(input);
 
-    races
-        .iter()
-        .map(|race| {
-            (1..race.time).fold(0, |acc, speed| {
-                let remaining_time = race.time - speed;
-                let max_distance = remaining_time * speed;
-                if max_distance > race.distance {
-                    return acc + 1;
-                }
-                acc
-            })
-        })
-        .product()
+    races.iter().map(|race| model.winning_holds(race)).product()
 }
 
-fn process_part_2(input: &str) -> u64 {
+fn process_part_2(input: &str, model: &dyn RaceModel) -> BigUint {
     let race = get_race(input);
 
-    (1..race.time).fold(0, |acc, speed| {
-        let remaining_time = race.time - speed;
-        let max_distance = remaining_time * speed;
-        if max_distance > race.distance {
-            return acc + 1;
-        }
-        acc
-    })
+    model.winning_holds(&race)
 }
 
 #[cfg(test)]
@@ -96,9 +177,9 @@ mod tests {
         let input = "Time:      7  15   30
 Distance:  9  40  200";
 
-        let result = process_part_1(input);
+        let result = process_part_1(input, &Standard);
 
-        assert_eq!(result, 288);
+        assert_eq!(result, 288u32.into());
     }
 
     #[test]
@@ -106,8 +187,44 @@ Distance:  9  40  200";
         let input = "Time:      7  15   30
 Distance:  9  40  200";
 
-        let result = process_part_2(input);
+        let result = process_part_2(input, &Standard);
+
+        assert_eq!(result, 71503u32.into());
+    }
+
+    #[test]
+    fn window() {
+        for time in 0..60 {
+            for distance in 0..(time * time / 4 + 2) {
+                let race = Race { time, distance };
+                let expected = (1..time)
+                    .filter(|hold| hold * (time - hold) > distance)
+                    .count();
+
+                let result = race.window().map_or(0, |window| window.count());
+
+                assert_eq!(result, expected, "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn wide_races() {
+        // time = 2k, record = k^2 - 1 wins only by holding for exactly k
+        let k = "9".repeat(120);
+        let k: BigUint = k.parse().unwrap();
+        let input = format!("Time: 7 {}\nDistance: 9 {}", &k * 2u32, &k * &k - 1u32);
+
+        assert_eq!(process_part_1(&input, &Standard), 4u32.into());
+        assert!(get_races(&input).is_err());
+    }
+
+    #[test]
+    fn part_2_input() {
+        let input = include_str!("./input.txt");
+
+        let result = process_part_2(input, &Standard);
 
-        assert_eq!(result, 71503);
+        assert_eq!(result, 42948149u32.into());
     }
 }
@@ -0,0 +1,148 @@
+use num_bigint::BigUint;
+
+use crate::wide::WideRace;
+
+// How far the boat travels for a given hold, and so how many holds beat the record. Races are
+// wide so part 2's joined record works with every model.
+pub trait RaceModel {
+    fn winning_holds(&self, race: &WideRace) -> BigUint;
+}
+
+// Speed equals hold time.
+pub struct Standard;
+
+impl RaceModel for Standard {
+    fn winning_holds(&self, race: &WideRace) -> BigUint {
+        race.winning_holds()
+    }
+}
+
+// Each millisecond held adds this much speed. Distances are then multiples of the rate, so beating
+// the record means beating the record divided by the rate, rounded down.
+pub struct Acceleration(pub u64);
+
+impl RaceModel for Acceleration {
+    fn winning_holds(&self, race: &WideRace) -> BigUint {
+        if self.0 == 0 {
+            return BigUint::default();
+        }
+        WideRace {
+            distance: &race.distance / self.0,
+            time: race.time.clone(),
+        }
+        .winning_holds()
+    }
+}
+
+// Holding past the cap wastes time without adding speed.
+pub struct SpeedCap(pub u64);
+
+impl RaceModel for SpeedCap {
+    fn winning_holds(&self, race: &WideRace) -> BigUint {
+        let cap = BigUint::from(self.0);
+        let mut total = BigUint::default();
+
+        // holds up to the cap behave as in the standard model
+        if let Some((first, last)) = race.window() {
+            let last = last.min(cap.clone());
+            if last >= first {
+                total += last + 1u32 - first;
+            }
+        }
+
+        // past the cap the distance is cap * (time - hold), which falls as the hold grows, and
+        // beats the record while time - hold > distance / cap
+        if self.0 > 0 {
+            let end = &race.distance / &cap + &cap + 1u32;
+            if race.time > end {
+                total += &race.time - end;
+            }
+        }
+        total
+    }
+}
+
+// The boat can't move until this many milliseconds after the race starts, which is the same as
+// a shorter race.
+pub struct StartDelay(pub u64);
+
+impl RaceModel for StartDelay {
+    fn winning_holds(&self, race: &WideRace) -> BigUint {
+        if race.time < BigUint::from(self.0) {
+            return BigUint::default();
+        }
+        WideRace {
+            distance: race.distance.clone(),
+            time: &race.time - self.0,
+        }
+        .winning_holds()
+    }
+}
+
+impl std::str::FromStr for Box<dyn RaceModel> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "standard" {
+            return Ok(Box::new(Standard));
+        }
+        let Some((name, value)) = s.split_once(':') else {
+            return Err(format!("unknown race model '{s}'"));
+        };
+        let value = value
+            .parse::<u64>()
+            .map_err(|_| format!("bad value '{value}' for race model '{name}'"))?;
+        match name {
+            "acceleration" => Ok(Box::new(Acceleration(value))),
+            "cap" => Ok(Box::new(SpeedCap(value))),
+            "delay" => Ok(Box::new(StartDelay(value))),
+            _ => Err(format!("unknown race model '{s}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // distance travelled for (hold, time)
+    type Distance = fn(u64, u64) -> u64;
+
+    #[test]
+    fn models() {
+        let models: Vec<(&str, Distance)> = vec![
+            ("standard", |hold, time| hold * (time - hold)),
+            ("acceleration:3", |hold, time| 3 * hold * (time - hold)),
+            ("acceleration:0", |_, _| 0),
+            ("cap:4", |hold, time| hold.min(4) * (time - hold)),
+            ("cap:0", |_, _| 0),
+            ("delay:5", |hold, time| hold * time.saturating_sub(hold + 5)),
+        ];
+
+        for (spec, distance) in models {
+            let model: Box<dyn RaceModel> = spec.parse().unwrap();
+            for time in 0..40 {
+                for record in 0..(time * time + 2) {
+                    let race = WideRace {
+                        distance: record.into(),
+                        time: time.into(),
+                    };
+                    let expected = (1..time)
+                        .filter(|hold| distance(*hold, time) > record)
+                        .count();
+
+                    let result = model.winning_holds(&race);
+
+                    assert_eq!(result, expected.into(), "{spec} {time} {record}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_model() {
+        assert!("warp".parse::<Box<dyn RaceModel>>().is_err());
+        assert!("cap:x".parse::<Box<dyn RaceModel>>().is_err());
+        assert!("warp:1".parse::<Box<dyn RaceModel>>().is_err());
+    }
+}
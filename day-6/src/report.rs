@@ -0,0 +1,105 @@
+use std::ops::RangeInclusive;
+
+use crate::Race;
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub time: u64,
+    pub record: u64,
+    pub window: Option<RangeInclusive<u64>>,
+    pub best_hold: u64,
+    pub best_distance: u128,
+    // how far the best hold beats the record by, negative when the race can't be won
+    pub margin: i128,
+}
+
+// Distance peaks at half the race time; when the time is odd both neighbours tie.
+pub fn report(race: &Race) -> Report {
+    let best_hold = race.time / 2;
+    let best_distance = u128::from(best_hold) * u128::from(race.time - best_hold);
+    Report {
+        time: race.time,
+        record: race.distance,
+        window: race.window(),
+        best_hold,
+        best_distance,
+        margin: best_distance as i128 - i128::from(race.distance),
+    }
+}
+
+pub fn to_table(reports: &[Report]) -> String {
+    let mut table = format!(
+        "{:>4} {:>8} {:>12} {:>8} {:>8} {:>8} {:>12} {:>12}\n",
+        "race", "time", "record", "first", "last", "best", "distance", "margin"
+    );
+    for (i, report) in reports.iter().enumerate() {
+        let (first, last) = match &report.window {
+            Some(window) => (window.start().to_string(), window.end().to_string()),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        table.push_str(&format!(
+            "{:>4} {:>8} {:>12} {first:>8} {last:>8} {:>8} {:>12} {:>12}\n",
+            i + 1,
+            report.time,
+            report.record,
+            report.best_hold,
+            report.best_distance,
+            report.margin
+        ));
+    }
+    table
+}
+
+pub fn to_json(reports: &[Report]) -> String {
+    let reports: Vec<_> = reports
+        .iter()
+        .enumerate()
+        .map(|(i, report)| {
+            let window = match &report.window {
+                Some(window) => format!("[{},{}]", window.start(), window.end()),
+                None => "null".to_string(),
+            };
+            format!(
+                "{{\"race\":{},\"time\":{},\"record\":{},\"window\":{window},\"best_hold\":{},\"best_distance\":{},\"margin\":{}}}",
+                i + 1,
+                report.time,
+                report.record,
+                report.best_hold,
+                report.best_distance,
+                report.margin
+            )
+        })
+        .collect();
+    format!("[{}]", reports.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_races;
+
+    #[test]
+    fn race_reports() {
+        let input = "Time:      7  15   30  4
+Distance:  9  40  200  4";
+        let reports: Vec<_> = get_races(input).unwrap().iter().map(report).collect();
+
+        let windows: Vec<_> = reports.iter().map(|r| r.window.clone()).collect();
+        assert_eq!(
+            windows,
+            vec![Some(2..=5), Some(4..=11), Some(11..=19), None]
+        );
+        let best: Vec<_> = reports
+            .iter()
+            .map(|r| (r.best_hold, r.best_distance, r.margin))
+            .collect();
+        assert_eq!(
+            best,
+            vec![(3, 12, 3), (7, 56, 16), (15, 225, 25), (2, 4, 0)]
+        );
+        assert_eq!(
+            to_json(&reports[3..]),
+            "[{\"race\":1,\"time\":4,\"record\":4,\"window\":null,\"best_hold\":2,\"best_distance\":4,\"margin\":0}]"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+
+use crate::Race;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Goal {
+    Wins,
+    Margin,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub time: u64,
+    pub hold: u64,
+    pub distance: u128,
+    pub won: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Plan {
+    pub entries: Vec<Entry>,
+    pub wins: usize,
+    // summed over the races won
+    pub margin: u128,
+}
+
+// The furthest a boat gets in `time`, holding for half of it.
+fn best_distance(time: u64) -> u128 {
+    u128::from(time / 2) * u128::from(time - time / 2)
+}
+
+// The least time that beats the record.
+fn minimum_time(record: u64) -> u64 {
+    let mut time = (4 * u128::from(record)).isqrt() as u64;
+    while best_distance(time) <= u128::from(record) {
+        time += 1;
+    }
+    time
+}
+
+// (time given to each race, total margin)
+type Allocation = (Vec<u64>, u128);
+
+// Trying every set of races is 2^n, so margin plans are limited to this many.
+const MAX_MARGIN_RACES: usize = 16;
+
+// Races not entered get no time. The best distance grows faster the longer the race, so for a set
+// of races entered the margin is largest when all but one get their minimum time and the last
+// gets whatever is left; each choice of last race is tried.
+fn allocate(races: &[Race], minimums: &[u64], entered: &[usize], budget: u64) -> Allocation {
+    let needed: u64 = entered.iter().map(|i| minimums[*i]).sum();
+    let mut times = vec![0; races.len()];
+    for i in entered {
+        times[*i] = minimums[*i];
+    }
+    let margin = |times: &[u64]| -> u128 {
+        entered
+            .iter()
+            .map(|i| best_distance(times[*i]) - u128::from(races[*i].distance))
+            .sum()
+    };
+
+    let mut best = (times.clone(), margin(&times));
+    for last in entered {
+        let mut times = times.clone();
+        times[*last] += budget - needed;
+        let margin = margin(&times);
+        if margin > best.1 {
+            best = (times, margin);
+        }
+    }
+    best
+}
+
+// Winning the most races means entering the cheapest ones first. The most margin means trying
+// every set of races that fits in the budget.
+pub fn optimise(races: &[Race], budget: u64, goal: Goal) -> Result<Plan, String> {
+    let minimums: Vec<_> = races.iter().map(|r| minimum_time(r.distance)).collect();
+
+    let (entered, (times, margin)) = match goal {
+        Goal::Wins => {
+            let mut cheapest: Vec<_> = (0..races.len()).collect();
+            // between races that cost the same, the lower record leaves more margin
+            cheapest.sort_by_key(|i| (minimums[*i], races[*i].distance));
+            let mut needed = 0u64;
+            let entered: Vec<_> = cheapest
+                .into_iter()
+                .take_while(|i| {
+                    needed = needed.saturating_add(minimums[*i]);
+                    needed <= budget
+                })
+                .collect();
+            let allocation = allocate(races, &minimums, &entered, budget);
+            (entered, allocation)
+        }
+        Goal::Margin => {
+            if races.len() > MAX_MARGIN_RACES {
+                return Err(format!(
+                    "can't plan for margin over {} races, at most {MAX_MARGIN_RACES}",
+                    races.len()
+                ));
+            }
+            let mut best: Option<(Vec<usize>, Allocation)> = None;
+            for set in 0..(1u32 << races.len()) {
+                let entered: Vec<_> = (0..races.len()).filter(|i| set & (1 << i) != 0).collect();
+                let needed = entered
+                    .iter()
+                    .try_fold(0u64, |total, i| total.checked_add(minimums[*i]));
+                if needed.is_none_or(|needed| needed > budget) {
+                    continue;
+                }
+                let allocation = allocate(races, &minimums, &entered, budget);
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_entered, (_, best_margin))| {
+                        (allocation.1, entered.len()) > (*best_margin, best_entered.len())
+                    })
+                {
+                    best = Some((entered, allocation));
+                }
+            }
+            best.unwrap()
+        }
+    };
+
+    let entries = times
+        .iter()
+        .zip(races)
+        .map(|(time, race)| Entry {
+            time: *time,
+            hold: time / 2,
+            distance: best_distance(*time),
+            won: best_distance(*time) > u128::from(race.distance),
+        })
+        .collect();
+    Ok(Plan {
+        entries,
+        wins: entered.len(),
+        margin,
+    })
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4} {:>8} {:>8} {:>12}",
+            "race", "time", "hold", "distance"
+        )?;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let result = if entry.won { "won" } else { "-" };
+            writeln!(
+                f,
+                "{:>4} {:>8} {:>8} {:>12}  {result}",
+                i + 1,
+                entry.time,
+                entry.hold,
+                entry.distance
+            )?;
+        }
+        writeln!(f, "{} won, margin {}", self.wins, self.margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_races;
+
+    const EXAMPLE: &str = "Time:      7  15   30
+Distance:  9  40  200";
+
+    #[test]
+    fn plans() {
+        let races = get_races(EXAMPLE).unwrap();
+
+        let plan = optimise(&races, 20, Goal::Wins).unwrap();
+        let times: Vec<_> = plan.entries.iter().map(|e| e.time).collect();
+        assert_eq!((times, plan.wins, plan.margin), (vec![7, 13, 0], 2, 5));
+
+        let plan = optimise(&races, 20, Goal::Margin).unwrap();
+        let times: Vec<_> = plan.entries.iter().map(|e| e.time).collect();
+        assert_eq!((times, plan.wins, plan.margin), (vec![20, 0, 0], 1, 91));
+
+        let plan = optimise(&races, 49, Goal::Wins).unwrap();
+        assert_eq!((plan.wins, plan.margin), (3, 15));
+
+        assert_eq!(optimise(&races, 6, Goal::Wins).unwrap().wins, 0);
+
+        let many: Vec<_> = (0..64)
+            .map(|_| Race {
+                distance: 9,
+                time: 7,
+            })
+            .collect();
+        let plan = optimise(&many, 7 * 64, Goal::Wins).unwrap();
+        assert_eq!((plan.wins, plan.margin), (64, 3 * 64));
+        assert!(optimise(&many, 7 * 64, Goal::Margin).is_err());
+    }
+
+    #[test]
+    fn matches_every_split() {
+        let races = get_races(EXAMPLE).unwrap();
+
+        for budget in 0..40 {
+            let mut best_wins = (0, 0);
+            let mut best_margin = (0, 0);
+            for a in 0..=budget {
+                for b in 0..=(budget - a) {
+                    let times = [a, b, budget - a - b];
+                    let won: Vec<_> = times
+                        .iter()
+                        .zip(&races)
+                        .filter(|(time, race)| best_distance(**time) > u128::from(race.distance))
+                        .map(|(time, race)| best_distance(*time) - u128::from(race.distance))
+                        .collect();
+                    let score = (won.len() as u128, won.iter().sum::<u128>());
+                    best_wins = best_wins.max(score);
+                    best_margin = best_margin.max((score.1, score.0));
+                }
+            }
+
+            let plan = optimise(&races, budget, Goal::Wins).unwrap();
+            assert_eq!(plan.wins as u128, best_wins.0, "{budget}");
+            let plan = optimise(&races, budget, Goal::Margin).unwrap();
+            assert_eq!((plan.margin, plan.wins as u128), best_margin, "{budget}");
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use num_bigint::BigUint;
+
+use crate::Race;
+
+// A race whose record is too long for a u64, such as part 2 with every digit group joined.
+#[derive(Debug, PartialEq)]
+pub struct WideRace {
+    pub distance: BigUint,
+    pub time: BigUint,
+}
+
+impl WideRace {
+    // The same search as `Race::window`, in arbitrary precision. Races that fit in a u64 take the
+    // fixed-width path.
+    pub fn window(&self) -> Option<(BigUint, BigUint)> {
+        if let (Ok(time), Ok(distance)) = (u64::try_from(&self.time), u64::try_from(&self.distance))
+        {
+            let window = Race { distance, time }.window()?;
+            return Some(((*window.start()).into(), (*window.end()).into()));
+        }
+
+        let time = &self.time;
+        let distance = &self.distance;
+        let wins = |hold: &BigUint| hold * (time - hold) > *distance;
+
+        let squared = time * time;
+        let quadrupled = distance * 4u32;
+        if squared < quadrupled {
+            return None;
+        }
+        let half = time / 2u32;
+        let mut first = (time - (squared - quadrupled).sqrt()) / 2u32;
+        while first > BigUint::default() && wins(&(&first - 1u32)) {
+            first -= 1u32;
+        }
+        while first <= half && !wins(&first) {
+            first += 1u32;
+        }
+        if first > half {
+            return None;
+        }
+        let last = time - &first;
+        Some((first, last))
+    }
+
+    pub fn winning_holds(&self) -> BigUint {
+        self.window()
+            .map_or(BigUint::default(), |(first, last)| last + 1u32 - first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_fixed_width() {
+        for (time, distance) in [(7u64, 9u64), (30, 200), (71530, 940200), (u64::MAX, 1)] {
+            let wide = WideRace {
+                distance: distance.into(),
+                time: time.into(),
+            };
+
+            let expected = Race { distance, time }
+                .window()
+                .map(|window| ((*window.start()).into(), (*window.end()).into()));
+
+            assert_eq!(wide.window(), expected);
+        }
+    }
+
+    #[test]
+    fn hundreds_of_digits() {
+        // time = 2k, distance = k^2 - j^2 wins for every hold strictly within j of k
+        let k: BigUint = "7".repeat(300).parse().unwrap();
+        let j: BigUint = "3".repeat(150).parse().unwrap();
+        let race = WideRace {
+            distance: &k * &k - &j * &j,
+            time: &k * 2u32,
+        };
+
+        assert_eq!(race.winning_holds(), &j * 2u32 - 1u32);
+
+        let race = WideRace {
+            distance: &k * &k,
+            time: &k * 2u32,
+        };
+
+        assert_eq!(race.winning_holds(), BigUint::default());
+    }
+}